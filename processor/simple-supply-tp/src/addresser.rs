@@ -6,6 +6,13 @@ pub const FAMILY_VERSION: &str = "0.1";
 const AGENT_PREFIX: &str = "00";
 const RECORD_PREFIX: &str = "01";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressSpace {
+    Agent,
+    Record,
+    OtherFamily,
+}
+
 pub fn get_agent_address(public_key: &str) -> String {
     let mut sha = Sha512::new();
     sha.input(public_key.as_bytes());
@@ -18,8 +25,26 @@ pub fn get_record_address(record_id: &str) -> String {
     get_namespace() + RECORD_PREFIX + &sha.result_str()[..62].to_string()
 }
 
+/// Prefix shared by every record address, for transactions which read
+/// records they can't name in advance
+pub fn get_record_prefix() -> String {
+    get_namespace() + RECORD_PREFIX
+}
+
 pub fn get_namespace() -> String {
     let mut sha = Sha512::new();
     sha.input_str(FAMILY_NAME);
     sha.result_str()[..6].to_string()
 }
+
+pub fn get_address_type(address: &str) -> AddressSpace {
+    let namespace = get_namespace();
+    if !address.starts_with(&namespace) {
+        return AddressSpace::OtherFamily;
+    }
+    match address.get(namespace.len()..namespace.len() + AGENT_PREFIX.len()) {
+        Some(AGENT_PREFIX) => AddressSpace::Agent,
+        Some(RECORD_PREFIX) => AddressSpace::Record,
+        _ => AddressSpace::OtherFamily,
+    }
+}
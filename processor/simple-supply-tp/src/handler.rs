@@ -1,5 +1,8 @@
-use protobuf::{CodedInputStream, Message};
+use protobuf::{CodedInputStream, Message, RepeatedField};
 use protos::payload::{SimpleSupplyPayload, SimpleSupplyPayload_Action};
+use protos::record::{
+    Record, Record_Location, Record_Owner, Record_PropertyBound, Record_PropertyReport,
+};
 use sawtooth_sdk::messages::processor::TpProcessRequest;
 use sawtooth_sdk::processor::handler::ApplyError;
 use sawtooth_sdk::processor::handler::TransactionContext;
@@ -8,6 +11,13 @@ use sawtooth_sdk::processor::handler::TransactionHandler;
 use crate::addresser::{get_namespace, FAMILY_NAME, FAMILY_VERSION};
 use crate::state::SimpleSupplyState;
 
+/// Emitted when a record is recalled, so that subscribers can flag every
+/// assembly the record ended up in
+pub const RECORD_RECALLED_EVENT: &str = "simple_supply/record_recalled";
+
+/// Emitted when a reported property value moves outside of its bound
+pub const THRESHOLD_VIOLATION_EVENT: &str = "simple_supply/threshold_violation";
+
 pub struct SimpleSupplyTransactionHandler {
     family_name: String,
     family_versions: Vec<String>,
@@ -49,19 +59,23 @@ impl TransactionHandler for SimpleSupplyTransactionHandler {
 
         let mut state = SimpleSupplyState::new(context);
 
+        let signer = request.get_header().get_signer_public_key();
+
         match payload.get_action() {
-            SimpleSupplyPayload_Action::CREATE_AGENT => {
-                return create_agent(
-                    &mut state,
-                    request.get_header().get_signer_public_key(),
-                    payload,
-                );
+            SimpleSupplyPayload_Action::CREATE_AGENT => create_agent(&mut state, signer, payload),
+            SimpleSupplyPayload_Action::CREATE_RECORD => create_record(&mut state, signer, payload),
+            SimpleSupplyPayload_Action::UPDATE_RECORD => update_record(&mut state, signer, payload),
+            SimpleSupplyPayload_Action::TRANSFER_RECORD => {
+                transfer_record(&mut state, signer, payload)
+            }
+            SimpleSupplyPayload_Action::ASSEMBLE_RECORD => {
+                assemble_record(&mut state, signer, payload)
             }
-            SimpleSupplyPayload_Action::CREATE_RECORD => {}
-            SimpleSupplyPayload_Action::UPDATE_RECORD => {}
-            SimpleSupplyPayload_Action::TRANSFER_RECORD => {}
+            SimpleSupplyPayload_Action::DISASSEMBLE_RECORD => {
+                disassemble_record(&mut state, signer, payload)
+            }
+            SimpleSupplyPayload_Action::RECALL_RECORD => recall_record(&mut state, signer, payload),
         }
-        Ok(())
     }
 }
 
@@ -81,12 +95,838 @@ fn create_agent(
         Err(e) => return Err(e),
     }
 
-    println!("{}", public_key);
-    println!("{:?}", payload);
-
     state.set_agent(
         public_key,
         payload.get_create_agent().get_name(),
         payload.get_timestamp(),
     )
 }
+
+fn create_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_create_record();
+    ensure_agent_exists(state, public_key)?;
+
+    if state.get_record(action.get_record_id())?.is_some() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Record {} already exists",
+            action.get_record_id(),
+        )));
+    }
+
+    validate_location(action.get_latitude(), action.get_longitude())?;
+
+    let mut bounds: Vec<Record_PropertyBound> = Vec::new();
+    for bound in action.get_bounds() {
+        if bound.get_min() > bound.get_max() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Bound for {} has a min greater than its max",
+                bound.get_name(),
+            )));
+        }
+        if bounds.iter().any(|b| b.get_name() == bound.get_name()) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Duplicate bound for {}",
+                bound.get_name(),
+            )));
+        }
+        bounds.push(Record_PropertyBound {
+            name: String::from(bound.get_name()),
+            min: bound.get_min(),
+            max: bound.get_max(),
+            ..Default::default()
+        });
+    }
+
+    let owner = Record_Owner {
+        agent_id: String::from(public_key),
+        timestamp: payload.get_timestamp(),
+        ..Default::default()
+    };
+    let location = Record_Location {
+        latitude: action.get_latitude(),
+        longitude: action.get_longitude(),
+        timestamp: payload.get_timestamp(),
+        ..Default::default()
+    };
+    let record = Record {
+        record_id: String::from(action.get_record_id()),
+        owners: ::protobuf::RepeatedField::from_vec(vec![owner]),
+        locations: ::protobuf::RepeatedField::from_vec(vec![location]),
+        bounds: ::protobuf::RepeatedField::from_vec(bounds),
+        ..Default::default()
+    };
+
+    state.set_record(action.get_record_id(), record)
+}
+
+fn update_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_update_record();
+    ensure_agent_exists(state, public_key)?;
+    let mut record = get_owned_record(state, public_key, action.get_record_id())?;
+
+    validate_location(action.get_latitude(), action.get_longitude())?;
+
+    record.locations.push(Record_Location {
+        latitude: action.get_latitude(),
+        longitude: action.get_longitude(),
+        timestamp: payload.get_timestamp(),
+        ..Default::default()
+    });
+
+    let mut violations = Vec::new();
+    for property in action.get_properties() {
+        let previous = record
+            .get_reports()
+            .iter()
+            .rev()
+            .find(|report| report.get_name() == property.get_name())
+            .map(|report| report.get_value());
+
+        if let Some(bound) = record
+            .get_bounds()
+            .iter()
+            .find(|bound| bound.get_name() == property.get_name())
+        {
+            let in_bound = |value: i64| bound.get_min() <= value && value <= bound.get_max();
+            // Only report the crossing, not every reading taken while the
+            // value stays out of bounds
+            let was_in_bound = previous.map(in_bound).unwrap_or(true);
+            if was_in_bound && !in_bound(property.get_value()) {
+                violations.push(vec![
+                    (
+                        String::from("record_id"),
+                        String::from(action.get_record_id()),
+                    ),
+                    (String::from("name"), String::from(property.get_name())),
+                    (String::from("value"), property.get_value().to_string()),
+                    (String::from("min"), bound.get_min().to_string()),
+                    (String::from("max"), bound.get_max().to_string()),
+                    (
+                        String::from("timestamp"),
+                        payload.get_timestamp().to_string(),
+                    ),
+                ]);
+            }
+        }
+
+        record.reports.push(Record_PropertyReport {
+            name: String::from(property.get_name()),
+            value: property.get_value(),
+            timestamp: payload.get_timestamp(),
+            ..Default::default()
+        });
+    }
+
+    // The record stays in violation until every bounded property is back
+    // within its bound
+    let in_violation = record.get_bounds().iter().any(|bound| {
+        record
+            .get_reports()
+            .iter()
+            .rev()
+            .find(|report| report.get_name() == bound.get_name())
+            .map(|report| {
+                report.get_value() < bound.get_min() || report.get_value() > bound.get_max()
+            })
+            .unwrap_or(false)
+    });
+    record.set_in_violation(in_violation);
+    state.set_record(action.get_record_id(), record)?;
+
+    for attributes in violations {
+        state.add_event(THRESHOLD_VIOLATION_EVENT, attributes)?;
+    }
+    Ok(())
+}
+
+fn transfer_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_transfer_record();
+    ensure_agent_exists(state, public_key)?;
+    ensure_agent_exists(state, action.get_receiving_agent())?;
+    let mut record = get_owned_record(state, public_key, action.get_record_id())?;
+
+    if !record.get_assembly().is_empty() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Record {} is a component of {} and cannot be transferred on its own",
+            action.get_record_id(),
+            record.get_assembly(),
+        )));
+    }
+
+    record.owners.push(Record_Owner {
+        agent_id: String::from(action.get_receiving_agent()),
+        timestamp: payload.get_timestamp(),
+        ..Default::default()
+    });
+
+    state.set_record(action.get_record_id(), record)
+}
+
+fn assemble_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_assemble_record();
+    ensure_agent_exists(state, public_key)?;
+    let mut assembly = get_owned_record(state, public_key, action.get_record_id())?;
+
+    if action.get_component_ids().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "At least one component is required",
+        )));
+    }
+
+    for component_id in action.get_component_ids() {
+        if component_id == action.get_record_id() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} cannot be a component of itself",
+                component_id,
+            )));
+        }
+        if assembly.get_components().contains(component_id) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} is already a component of {}",
+                component_id,
+                action.get_record_id(),
+            )));
+        }
+
+        let mut component = get_owned_record(state, public_key, component_id)?;
+        // A recall marks the assemblies a record is already in, so a recalled
+        // record must not be built into new ones after the fact
+        if component.get_recalled() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} has been recalled and cannot be assembled",
+                component_id,
+            )));
+        }
+        if !component.get_assembly().is_empty() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} is already a component of {}",
+                component_id,
+                component.get_assembly(),
+            )));
+        }
+        if is_assembled_into(state, action.get_record_id(), component_id)? {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} is already assembled into {}",
+                action.get_record_id(),
+                component_id,
+            )));
+        }
+
+        component.set_assembly(String::from(action.get_record_id()));
+        state.set_record(component_id, component)?;
+        assembly.components.push(component_id.clone());
+    }
+
+    state.set_record(action.get_record_id(), assembly)
+}
+
+fn disassemble_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_disassemble_record();
+    ensure_agent_exists(state, public_key)?;
+    let mut assembly = get_owned_record(state, public_key, action.get_record_id())?;
+
+    if action.get_component_ids().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "At least one component is required",
+        )));
+    }
+
+    for component_id in action.get_component_ids() {
+        if !assembly.get_components().contains(component_id) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} is not a component of {}",
+                component_id,
+                action.get_record_id(),
+            )));
+        }
+
+        let mut component = match state.get_record(component_id)? {
+            Some(component) => component,
+            None => {
+                return Err(ApplyError::InvalidTransaction(format!(
+                    "Record {} does not exist",
+                    component_id,
+                )))
+            }
+        };
+        component.clear_assembly();
+
+        // The component travelled with the assembly, so whoever holds the
+        // assembly now takes ownership of the released component
+        if get_owner(&component) != Some(public_key) {
+            component.owners.push(Record_Owner {
+                agent_id: String::from(public_key),
+                timestamp: payload.get_timestamp(),
+                ..Default::default()
+            });
+        }
+
+        state.set_record(component_id, component)?;
+        let components = assembly
+            .take_components()
+            .into_iter()
+            .filter(|assembled_id| assembled_id != component_id)
+            .collect();
+        assembly.set_components(RepeatedField::from_vec(components));
+    }
+
+    state.set_record(action.get_record_id(), assembly)
+}
+
+fn recall_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    payload: SimpleSupplyPayload,
+) -> Result<(), ApplyError> {
+    let action = payload.get_recall_record();
+    ensure_agent_exists(state, public_key)?;
+    let mut record = match state.get_record(action.get_record_id())? {
+        Some(record) => record,
+        None => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} does not exist",
+                action.get_record_id(),
+            )))
+        }
+    };
+
+    let originator = record
+        .get_owners()
+        .first()
+        .map(|owner| owner.get_agent_id());
+    if originator != Some(public_key) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Only the originating agent can recall record {}",
+            action.get_record_id(),
+        )));
+    }
+    if record.get_recalled() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Record {} has already been recalled",
+            action.get_record_id(),
+        )));
+    }
+
+    record.set_recalled(true);
+    state.set_record(action.get_record_id(), record)?;
+
+    state.add_event(
+        RECORD_RECALLED_EVENT,
+        vec![
+            (
+                String::from("record_id"),
+                String::from(action.get_record_id()),
+            ),
+            (String::from("agent_id"), String::from(public_key)),
+            (
+                String::from("timestamp"),
+                payload.get_timestamp().to_string(),
+            ),
+        ],
+    )
+}
+
+fn ensure_agent_exists(state: &mut SimpleSupplyState, public_key: &str) -> Result<(), ApplyError> {
+    match state.get_agent(public_key)? {
+        Some(_) => Ok(()),
+        None => Err(ApplyError::InvalidTransaction(format!(
+            "Agent with the public key {} does not exist",
+            public_key,
+        ))),
+    }
+}
+
+fn get_owner(record: &Record) -> Option<&str> {
+    record.get_owners().last().map(|owner| owner.get_agent_id())
+}
+
+/// Fetches a record and checks that it is currently owned by the agent with
+/// the given public key
+fn get_owned_record(
+    state: &mut SimpleSupplyState,
+    public_key: &str,
+    record_id: &str,
+) -> Result<Record, ApplyError> {
+    let record = match state.get_record(record_id)? {
+        Some(record) => record,
+        None => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Record {} does not exist",
+                record_id,
+            )))
+        }
+    };
+
+    if get_owner(&record) != Some(public_key) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent with the public key {} is not the owner of record {}",
+            public_key, record_id,
+        )));
+    }
+    Ok(record)
+}
+
+/// Walks up the assembly chain of `record_id` looking for `assembly_id`, so
+/// that an assembly can never end up as one of its own components
+fn is_assembled_into(
+    state: &mut SimpleSupplyState,
+    record_id: &str,
+    assembly_id: &str,
+) -> Result<bool, ApplyError> {
+    let mut current = String::from(record_id);
+    loop {
+        let record = match state.get_record(&current)? {
+            Some(record) => record,
+            None => return Ok(false),
+        };
+        if record.get_assembly().is_empty() {
+            return Ok(false);
+        }
+        if record.get_assembly() == assembly_id {
+            return Ok(true);
+        }
+        current = String::from(record.get_assembly());
+    }
+}
+
+fn validate_location(latitude: i64, longitude: i64) -> Result<(), ApplyError> {
+    if !(-90_000_000..=90_000_000).contains(&latitude) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Latitude must be between -90 and 90. Got {}",
+            latitude as f64 / 1_000_000.0,
+        )));
+    }
+    if !(-180_000_000..=180_000_000).contains(&longitude) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Longitude must be between -180 and 180. Got {}",
+            longitude as f64 / 1_000_000.0,
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protos::payload::{
+        AssembleRecordAction, CreateAgentAction, CreateRecordAction, DisassembleRecordAction,
+        PropertyBound, PropertyValue, RecallRecordAction, TransferRecordAction, UpdateRecordAction,
+    };
+    use sawtooth_sdk::processor::handler::ContextError;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+
+    /// Keeps state and events in memory, in place of the validator
+    #[derive(Default)]
+    struct MockContext {
+        state: RefCell<HashMap<String, Vec<u8>>>,
+        events: RefCell<Vec<(String, Vec<(String, String)>)>>,
+    }
+
+    impl TransactionContext for MockContext {
+        fn get_state_entries(
+            &self,
+            addresses: &[String],
+        ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
+            let state = self.state.borrow();
+            Ok(addresses
+                .iter()
+                .filter_map(|address| {
+                    state
+                        .get(address)
+                        .map(|data| (address.clone(), data.clone()))
+                })
+                .collect())
+        }
+
+        fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
+            self.state.borrow_mut().extend(entries);
+            Ok(())
+        }
+
+        fn delete_state_entries(&self, addresses: &[String]) -> Result<Vec<String>, ContextError> {
+            let mut state = self.state.borrow_mut();
+            Ok(addresses
+                .iter()
+                .filter(|address| state.remove(*address).is_some())
+                .cloned()
+                .collect())
+        }
+
+        fn add_receipt_data(&self, _data: &[u8]) -> Result<(), ContextError> {
+            Ok(())
+        }
+
+        fn add_event(
+            &self,
+            event_type: String,
+            attributes: Vec<(String, String)>,
+            _data: &[u8],
+        ) -> Result<(), ContextError> {
+            self.events.borrow_mut().push((event_type, attributes));
+            Ok(())
+        }
+    }
+
+    impl MockContext {
+        /// Applies a transaction, discarding its changes if it is invalid as
+        /// the validator would
+        fn apply(&mut self, signer: &str, payload: SimpleSupplyPayload) -> Result<(), ApplyError> {
+            let mut request = TpProcessRequest::new();
+            request
+                .mut_header()
+                .set_signer_public_key(String::from(signer));
+            request.set_payload(payload.write_to_bytes().unwrap());
+
+            let state = self.state.borrow().clone();
+            let events = self.events.borrow().len();
+            let result = SimpleSupplyTransactionHandler::new().apply(&request, self);
+            if result.is_err() {
+                *self.state.borrow_mut() = state;
+                self.events.borrow_mut().truncate(events);
+            }
+            result
+        }
+
+        fn record(&mut self, record_id: &str) -> Record {
+            SimpleSupplyState::new(self)
+                .get_record(record_id)
+                .unwrap()
+                .unwrap()
+        }
+
+        fn event_types(&self) -> Vec<String> {
+            self.events
+                .borrow()
+                .iter()
+                .map(|(event_type, _)| event_type.clone())
+                .collect()
+        }
+    }
+
+    fn payload(action: SimpleSupplyPayload_Action) -> SimpleSupplyPayload {
+        let mut payload = SimpleSupplyPayload::new();
+        payload.set_action(action);
+        payload.set_timestamp(1);
+        payload
+    }
+
+    fn create_agent(name: &str) -> SimpleSupplyPayload {
+        let mut action = CreateAgentAction::new();
+        action.set_name(String::from(name));
+        let mut payload = payload(SimpleSupplyPayload_Action::CREATE_AGENT);
+        payload.set_create_agent(action);
+        payload
+    }
+
+    fn create_record(record_id: &str, bounds: &[(&str, i64, i64)]) -> SimpleSupplyPayload {
+        let mut action = CreateRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        for (name, min, max) in bounds {
+            let mut bound = PropertyBound::new();
+            bound.set_name(String::from(*name));
+            bound.set_min(*min);
+            bound.set_max(*max);
+            action.mut_bounds().push(bound);
+        }
+        let mut payload = payload(SimpleSupplyPayload_Action::CREATE_RECORD);
+        payload.set_create_record(action);
+        payload
+    }
+
+    fn update_record(record_id: &str, properties: &[(&str, i64)]) -> SimpleSupplyPayload {
+        let mut action = UpdateRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        for (name, value) in properties {
+            let mut property = PropertyValue::new();
+            property.set_name(String::from(*name));
+            property.set_value(*value);
+            action.mut_properties().push(property);
+        }
+        let mut payload = payload(SimpleSupplyPayload_Action::UPDATE_RECORD);
+        payload.set_update_record(action);
+        payload
+    }
+
+    fn transfer_record(record_id: &str, receiving_agent: &str) -> SimpleSupplyPayload {
+        let mut action = TransferRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        action.set_receiving_agent(String::from(receiving_agent));
+        let mut payload = payload(SimpleSupplyPayload_Action::TRANSFER_RECORD);
+        payload.set_transfer_record(action);
+        payload
+    }
+
+    fn assemble_record(record_id: &str, component_ids: &[&str]) -> SimpleSupplyPayload {
+        let mut action = AssembleRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        action.set_component_ids(component_ids.iter().map(|id| String::from(*id)).collect());
+        let mut payload = payload(SimpleSupplyPayload_Action::ASSEMBLE_RECORD);
+        payload.set_assemble_record(action);
+        payload
+    }
+
+    fn disassemble_record(record_id: &str, component_ids: &[&str]) -> SimpleSupplyPayload {
+        let mut action = DisassembleRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        action.set_component_ids(component_ids.iter().map(|id| String::from(*id)).collect());
+        let mut payload = payload(SimpleSupplyPayload_Action::DISASSEMBLE_RECORD);
+        payload.set_disassemble_record(action);
+        payload
+    }
+
+    fn recall_record(record_id: &str) -> SimpleSupplyPayload {
+        let mut action = RecallRecordAction::new();
+        action.set_record_id(String::from(record_id));
+        let mut payload = payload(SimpleSupplyPayload_Action::RECALL_RECORD);
+        payload.set_recall_record(action);
+        payload
+    }
+
+    fn assert_invalid(result: Result<(), ApplyError>) {
+        match result {
+            Err(ApplyError::InvalidTransaction(_)) => (),
+            other => panic!("expected an invalid transaction, got {:?}", other),
+        }
+    }
+
+    /// Alice and Bob as agents, with Alice owning `records`
+    fn setup(records: &[&str]) -> MockContext {
+        let mut context = MockContext::default();
+        context.apply(ALICE, create_agent("Alice")).unwrap();
+        context.apply(BOB, create_agent("Bob")).unwrap();
+        for record_id in records {
+            context.apply(ALICE, create_record(record_id, &[])).unwrap();
+        }
+        context
+    }
+
+    #[test]
+    fn create_record_keeps_its_bounds() {
+        let mut context = setup(&[]);
+        context
+            .apply(
+                ALICE,
+                create_record("fridge", &[("temperature", 0, 5), ("humidity", 20, 20)]),
+            )
+            .unwrap();
+        let bounds = context.record("fridge").take_bounds();
+        assert_eq!(
+            bounds
+                .iter()
+                .map(|bound| (bound.get_name(), bound.get_min(), bound.get_max()))
+                .collect::<Vec<_>>(),
+            [("temperature", 0, 5), ("humidity", 20, 20)]
+        );
+    }
+
+    #[test]
+    fn create_record_rejects_invalid_bounds() {
+        let mut context = setup(&[]);
+        // A min above its max
+        assert_invalid(context.apply(ALICE, create_record("fridge", &[("temperature", 6, 5)])));
+        // The same property bounded twice
+        assert_invalid(context.apply(
+            ALICE,
+            create_record("fridge", &[("temperature", 0, 5), ("temperature", 1, 4)]),
+        ));
+        // Neither left a record behind
+        assert!(SimpleSupplyState::new(&mut context)
+            .get_record("fridge")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn assemble_links_components_to_the_assembly() {
+        let mut context = setup(&["truck", "pallet", "crate"]);
+        context
+            .apply(ALICE, assemble_record("truck", &["pallet", "crate"]))
+            .unwrap();
+
+        assert_eq!(
+            context.record("truck").get_components(),
+            ["pallet", "crate"]
+        );
+        assert_eq!(context.record("pallet").get_assembly(), "truck");
+        assert_eq!(context.record("crate").get_assembly(), "truck");
+    }
+
+    #[test]
+    fn assemble_rejects_invalid_components() {
+        let mut context = setup(&["truck", "pallet", "van"]);
+        context.apply(BOB, create_record("bobs", &[])).unwrap();
+
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &[])));
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &["truck"])));
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &["missing"])));
+        // Only records the signer owns can be assembled
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &["bobs"])));
+        assert_invalid(context.apply(BOB, assemble_record("truck", &["pallet"])));
+
+        context
+            .apply(ALICE, assemble_record("truck", &["pallet"]))
+            .unwrap();
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &["pallet"])));
+        assert_invalid(context.apply(ALICE, assemble_record("van", &["pallet"])));
+    }
+
+    #[test]
+    fn assemble_rejects_cycles() {
+        let mut context = setup(&["truck", "pallet", "crate"]);
+        context
+            .apply(ALICE, assemble_record("truck", &["pallet"]))
+            .unwrap();
+        context
+            .apply(ALICE, assemble_record("pallet", &["crate"]))
+            .unwrap();
+
+        // The crate is inside the pallet, which is inside the truck, so
+        // neither can take the truck as a component
+        assert_invalid(context.apply(ALICE, assemble_record("crate", &["truck"])));
+        assert_invalid(context.apply(ALICE, assemble_record("pallet", &["truck"])));
+        assert!(context.record("truck").get_assembly().is_empty());
+    }
+
+    #[test]
+    fn components_cannot_be_transferred_on_their_own() {
+        let mut context = setup(&["truck", "pallet"]);
+        context
+            .apply(ALICE, assemble_record("truck", &["pallet"]))
+            .unwrap();
+
+        assert_invalid(context.apply(ALICE, transfer_record("pallet", BOB)));
+        context.apply(ALICE, transfer_record("truck", BOB)).unwrap();
+        assert_eq!(get_owner(&context.record("truck")), Some(BOB));
+        assert_eq!(get_owner(&context.record("pallet")), Some(ALICE));
+    }
+
+    #[test]
+    fn disassemble_hands_components_to_the_assembly_owner() {
+        let mut context = setup(&["truck", "pallet", "crate"]);
+        context
+            .apply(ALICE, assemble_record("truck", &["pallet", "crate"]))
+            .unwrap();
+        context.apply(ALICE, transfer_record("truck", BOB)).unwrap();
+
+        assert_invalid(context.apply(ALICE, disassemble_record("truck", &["pallet"])));
+        assert_invalid(context.apply(BOB, disassemble_record("truck", &["missing"])));
+        context
+            .apply(BOB, disassemble_record("truck", &["pallet"]))
+            .unwrap();
+
+        let pallet = context.record("pallet");
+        assert!(pallet.get_assembly().is_empty());
+        assert_eq!(get_owner(&pallet), Some(BOB));
+        assert_eq!(context.record("truck").get_components(), ["crate"]);
+        assert_invalid(context.apply(BOB, disassemble_record("truck", &["pallet"])));
+    }
+
+    #[test]
+    fn recalled_records_cannot_be_assembled() {
+        let mut context = setup(&["truck", "pallet"]);
+        context.apply(ALICE, recall_record("pallet")).unwrap();
+
+        assert_invalid(context.apply(ALICE, assemble_record("truck", &["pallet"])));
+        assert!(context.record("truck").get_components().is_empty());
+    }
+
+    #[test]
+    fn only_the_originator_can_recall() {
+        let mut context = setup(&["pallet"]);
+        context
+            .apply(ALICE, transfer_record("pallet", BOB))
+            .unwrap();
+
+        // Bob owns the pallet now, but Alice made it
+        assert_invalid(context.apply(BOB, recall_record("pallet")));
+        assert!(context.event_types().is_empty());
+
+        context.apply(ALICE, recall_record("pallet")).unwrap();
+        assert!(context.record("pallet").get_recalled());
+        assert_eq!(context.event_types(), [RECORD_RECALLED_EVENT]);
+        assert_invalid(context.apply(ALICE, recall_record("pallet")));
+    }
+
+    #[test]
+    fn violations_are_reported_when_crossing_the_bound() {
+        let mut context = setup(&[]);
+        context
+            .apply(ALICE, create_record("fridge", &[("temperature", 0, 5)]))
+            .unwrap();
+
+        context
+            .apply(ALICE, update_record("fridge", &[("temperature", 3)]))
+            .unwrap();
+        assert!(!context.record("fridge").get_in_violation());
+        assert!(context.event_types().is_empty());
+
+        context
+            .apply(ALICE, update_record("fridge", &[("temperature", 9)]))
+            .unwrap();
+        assert!(context.record("fridge").get_in_violation());
+        assert_eq!(context.event_types(), [THRESHOLD_VIOLATION_EVENT]);
+
+        // Staying out of bounds is not a new violation
+        context
+            .apply(ALICE, update_record("fridge", &[("temperature", 12)]))
+            .unwrap();
+        assert_eq!(context.event_types(), [THRESHOLD_VIOLATION_EVENT]);
+
+        context
+            .apply(ALICE, update_record("fridge", &[("temperature", 4)]))
+            .unwrap();
+        assert!(!context.record("fridge").get_in_violation());
+        assert_eq!(context.event_types(), [THRESHOLD_VIOLATION_EVENT]);
+    }
+
+    #[test]
+    fn violation_lasts_while_any_property_is_out_of_bounds() {
+        let mut context = setup(&[]);
+        context
+            .apply(
+                ALICE,
+                create_record("fridge", &[("temperature", 0, 5), ("humidity", 20, 60)]),
+            )
+            .unwrap();
+
+        context
+            .apply(
+                ALICE,
+                update_record("fridge", &[("temperature", 9), ("humidity", 80)]),
+            )
+            .unwrap();
+        context
+            .apply(ALICE, update_record("fridge", &[("temperature", 3)]))
+            .unwrap();
+        assert!(context.record("fridge").get_in_violation());
+
+        context
+            .apply(ALICE, update_record("fridge", &[("humidity", 40)]))
+            .unwrap();
+        assert!(!context.record("fridge").get_in_violation());
+    }
+}
@@ -1,5 +1,6 @@
-use protobuf::{CodedInputStream, Message};
+use protobuf::{CodedInputStream, Message, RepeatedField};
 use protos::agent::{Agent, AgentContainer};
+use protos::record::{Record, RecordContainer};
 use sawtooth_sdk::processor::handler::ApplyError;
 use sawtooth_sdk::processor::handler::TransactionContext;
 
@@ -72,4 +73,69 @@ impl<'a> SimpleSupplyState<'a> {
             .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
         Ok(())
     }
+
+    pub fn get_record(&mut self, record_id: &str) -> Result<Option<Record>, ApplyError> {
+        let address = get_record_address(record_id);
+        let state_entry = self.context.get_state_entry(&address)?;
+        match state_entry {
+            Some(data) => {
+                let record_container: RecordContainer = protobuf::parse_from_bytes(&data[..])
+                    .map_err(|_| {
+                        ApplyError::InternalError(String::from("failed to deserialize"))
+                    })?;
+
+                for record in record_container.get_entries() {
+                    if record.record_id == record_id {
+                        return Ok(Some(record.clone()));
+                    }
+                }
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_record(&mut self, record_id: &str, record: Record) -> Result<(), ApplyError> {
+        let mut record_container = RecordContainer::new();
+        let address = get_record_address(record_id);
+        let state_entry = self.context.get_state_entry(&address)?;
+        match state_entry {
+            Some(data) => {
+                record_container
+                    .merge_from(&mut CodedInputStream::from_bytes(&data[..]))
+                    .map_err(|_| {
+                        ApplyError::InternalError(String::from("failed to deserialize"))
+                    })?;
+            }
+            None => (),
+        }
+
+        // Replace any previous version of this record in the container
+        let entries = record_container
+            .take_entries()
+            .into_iter()
+            .filter(|entry| entry.record_id != record_id)
+            .collect();
+        record_container.set_entries(RepeatedField::from_vec(entries));
+        record_container.entries.push(record);
+
+        let data = record_container
+            .write_to_bytes()
+            .map_err(|_| ApplyError::InternalError(String::from("failed to serialize")))?;
+
+        self.context
+            .set_state_entry(address, data)
+            .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
+        Ok(())
+    }
+
+    pub fn add_event(
+        &mut self,
+        event_type: &str,
+        attributes: Vec<(String, String)>,
+    ) -> Result<(), ApplyError> {
+        self.context
+            .add_event(event_type.to_string(), attributes, &[])
+            .map_err(|err| ApplyError::InternalError(format!("{}", err)))
+    }
 }
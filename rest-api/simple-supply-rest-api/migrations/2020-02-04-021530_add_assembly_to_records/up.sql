@@ -0,0 +1 @@
+ALTER TABLE records ADD COLUMN IF NOT EXISTS assembly VARCHAR;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS record_recalls (
+    id                BIGSERIAL PRIMARY KEY,
+    record_id         VARCHAR,
+    source_record_id  VARCHAR,
+    timestamp         BIGINT,
+    start_block_num   BIGINT,
+    end_block_num     BIGINT
+);
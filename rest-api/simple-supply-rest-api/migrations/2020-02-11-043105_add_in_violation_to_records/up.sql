@@ -0,0 +1 @@
+ALTER TABLE records ADD COLUMN IF NOT EXISTS in_violation BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+ALTER TABLE auths
+    ALTER COLUMN hashed_password DROP NOT NULL,
+    ALTER COLUMN encrypted_private_key DROP NOT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE auths
+    ALTER COLUMN hashed_password SET NOT NULL,
+    ALTER COLUMN encrypted_private_key SET NOT NULL;
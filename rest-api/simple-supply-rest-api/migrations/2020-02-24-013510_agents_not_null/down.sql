@@ -0,0 +1,6 @@
+ALTER TABLE agents
+    ALTER COLUMN public_key DROP NOT NULL,
+    ALTER COLUMN name DROP NOT NULL,
+    ALTER COLUMN timestamp DROP NOT NULL,
+    ALTER COLUMN start_block_num DROP NOT NULL,
+    ALTER COLUMN end_block_num DROP NOT NULL;
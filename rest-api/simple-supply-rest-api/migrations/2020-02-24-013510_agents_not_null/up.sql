@@ -0,0 +1,6 @@
+ALTER TABLE agents
+    ALTER COLUMN public_key SET NOT NULL,
+    ALTER COLUMN name SET NOT NULL,
+    ALTER COLUMN timestamp SET NOT NULL,
+    ALTER COLUMN start_block_num SET NOT NULL,
+    ALTER COLUMN end_block_num SET NOT NULL;
@@ -0,0 +1,26 @@
+ALTER TABLE records
+    ALTER COLUMN record_id DROP NOT NULL,
+    ALTER COLUMN start_block_num DROP NOT NULL,
+    ALTER COLUMN end_block_num DROP NOT NULL;
+
+ALTER TABLE record_owners
+    ALTER COLUMN record_id DROP NOT NULL,
+    ALTER COLUMN agent_id DROP NOT NULL,
+    ALTER COLUMN timestamp DROP NOT NULL,
+    ALTER COLUMN start_block_num DROP NOT NULL,
+    ALTER COLUMN end_block_num DROP NOT NULL;
+
+ALTER TABLE record_locations
+    ALTER COLUMN record_id DROP NOT NULL,
+    ALTER COLUMN latitude DROP NOT NULL,
+    ALTER COLUMN longitude DROP NOT NULL,
+    ALTER COLUMN timestamp DROP NOT NULL,
+    ALTER COLUMN start_block_num DROP NOT NULL,
+    ALTER COLUMN end_block_num DROP NOT NULL;
+
+ALTER TABLE record_recalls
+    ALTER COLUMN record_id DROP NOT NULL,
+    ALTER COLUMN source_record_id DROP NOT NULL,
+    ALTER COLUMN timestamp DROP NOT NULL,
+    ALTER COLUMN start_block_num DROP NOT NULL,
+    ALTER COLUMN end_block_num DROP NOT NULL;
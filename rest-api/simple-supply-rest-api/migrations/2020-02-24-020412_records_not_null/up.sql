@@ -0,0 +1,26 @@
+ALTER TABLE records
+    ALTER COLUMN record_id SET NOT NULL,
+    ALTER COLUMN start_block_num SET NOT NULL,
+    ALTER COLUMN end_block_num SET NOT NULL;
+
+ALTER TABLE record_owners
+    ALTER COLUMN record_id SET NOT NULL,
+    ALTER COLUMN agent_id SET NOT NULL,
+    ALTER COLUMN timestamp SET NOT NULL,
+    ALTER COLUMN start_block_num SET NOT NULL,
+    ALTER COLUMN end_block_num SET NOT NULL;
+
+ALTER TABLE record_locations
+    ALTER COLUMN record_id SET NOT NULL,
+    ALTER COLUMN latitude SET NOT NULL,
+    ALTER COLUMN longitude SET NOT NULL,
+    ALTER COLUMN timestamp SET NOT NULL,
+    ALTER COLUMN start_block_num SET NOT NULL,
+    ALTER COLUMN end_block_num SET NOT NULL;
+
+ALTER TABLE record_recalls
+    ALTER COLUMN record_id SET NOT NULL,
+    ALTER COLUMN source_record_id SET NOT NULL,
+    ALTER COLUMN timestamp SET NOT NULL,
+    ALTER COLUMN start_block_num SET NOT NULL,
+    ALTER COLUMN end_block_num SET NOT NULL;
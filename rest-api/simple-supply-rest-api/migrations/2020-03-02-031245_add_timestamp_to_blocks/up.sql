@@ -0,0 +1 @@
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS timestamp BIGINT;
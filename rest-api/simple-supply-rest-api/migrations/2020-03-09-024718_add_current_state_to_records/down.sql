@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS records_position_idx;
+DROP INDEX IF EXISTS records_owner_idx;
+DROP INDEX IF EXISTS records_record_id_idx;
+
+ALTER TABLE records
+    DROP COLUMN IF EXISTS last_updated,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude,
+    DROP COLUMN IF EXISTS owner;
@@ -0,0 +1,48 @@
+ALTER TABLE records
+    ADD COLUMN IF NOT EXISTS owner VARCHAR,
+    ADD COLUMN IF NOT EXISTS latitude BIGINT,
+    ADD COLUMN IF NOT EXISTS longitude BIGINT,
+    ADD COLUMN IF NOT EXISTS last_updated BIGINT;
+
+-- Fill in existing rows from the owner and location history they were
+-- written alongside
+UPDATE records SET
+    owner = (
+        SELECT agent_id FROM record_owners
+        WHERE record_owners.record_id = records.record_id
+          AND record_owners.start_block_num <= records.start_block_num
+          AND record_owners.end_block_num > records.start_block_num
+        ORDER BY timestamp DESC, id DESC LIMIT 1
+    ),
+    latitude = (
+        SELECT latitude FROM record_locations
+        WHERE record_locations.record_id = records.record_id
+          AND record_locations.start_block_num <= records.start_block_num
+          AND record_locations.end_block_num > records.start_block_num
+        ORDER BY timestamp DESC, id DESC LIMIT 1
+    ),
+    longitude = (
+        SELECT longitude FROM record_locations
+        WHERE record_locations.record_id = records.record_id
+          AND record_locations.start_block_num <= records.start_block_num
+          AND record_locations.end_block_num > records.start_block_num
+        ORDER BY timestamp DESC, id DESC LIMIT 1
+    ),
+    last_updated = GREATEST(
+        (
+            SELECT MAX(timestamp) FROM record_owners
+            WHERE record_owners.record_id = records.record_id
+              AND record_owners.start_block_num <= records.start_block_num
+              AND record_owners.end_block_num > records.start_block_num
+        ),
+        (
+            SELECT MAX(timestamp) FROM record_locations
+            WHERE record_locations.record_id = records.record_id
+              AND record_locations.start_block_num <= records.start_block_num
+              AND record_locations.end_block_num > records.start_block_num
+        )
+    );
+
+CREATE INDEX IF NOT EXISTS records_record_id_idx ON records (record_id);
+CREATE INDEX IF NOT EXISTS records_owner_idx ON records (owner);
+CREATE INDEX IF NOT EXISTS records_position_idx ON records (latitude, longitude);
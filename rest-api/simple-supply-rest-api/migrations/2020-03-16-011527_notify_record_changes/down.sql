@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS records_notify_change ON records;
+DROP FUNCTION IF EXISTS notify_record_change();
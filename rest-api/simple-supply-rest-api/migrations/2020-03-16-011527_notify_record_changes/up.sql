@@ -0,0 +1,45 @@
+-- Announces every new version of a record on the record_changes channel, so
+-- the REST API can push changes to clients instead of being polled.
+-- Notifications are only delivered once the subscriber's transaction for the
+-- block commits.
+CREATE OR REPLACE FUNCTION notify_record_change() RETURNS trigger AS $$
+DECLARE
+    previous_owner VARCHAR;
+    previous_exists BOOLEAN;
+    change VARCHAR;
+BEGIN
+    -- The version being replaced was closed at the block of the new one
+    SELECT TRUE, owner INTO previous_exists, previous_owner
+    FROM records
+    WHERE record_id = NEW.record_id
+      AND end_block_num = NEW.start_block_num
+    ORDER BY id DESC
+    LIMIT 1;
+
+    IF previous_exists IS NULL THEN
+        change := 'created';
+    ELSIF previous_owner IS DISTINCT FROM NEW.owner THEN
+        change := 'transferred';
+    ELSE
+        change := 'updated';
+    END IF;
+
+    PERFORM pg_notify('record_changes', json_build_object(
+        'event', change,
+        'record_id', NEW.record_id,
+        'owner', NEW.owner,
+        'previous_owner', previous_owner,
+        'latitude', NEW.latitude,
+        'longitude', NEW.longitude,
+        'in_violation', NEW.in_violation,
+        'timestamp', NEW.last_updated,
+        'block_num', NEW.start_block_num
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS records_notify_change ON records;
+CREATE TRIGGER records_notify_change
+    AFTER INSERT ON records
+    FOR EACH ROW EXECUTE PROCEDURE notify_record_change();
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key  VARCHAR NOT NULL,
+    agent            VARCHAR NOT NULL,
+    request_hash     VARCHAR NOT NULL,
+    status_code      INTEGER,
+    content_type     VARCHAR,
+    response_body    BYTEA,
+    created_at       BIGINT NOT NULL,
+    PRIMARY KEY (idempotency_key, agent)
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_created_at_idx
+    ON idempotency_keys (created_at);
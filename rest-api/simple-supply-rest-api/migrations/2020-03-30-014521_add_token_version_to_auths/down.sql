@@ -0,0 +1,4 @@
+UPDATE auths SET encrypted_private_key = '' WHERE encrypted_private_key IS NULL;
+ALTER TABLE auths
+    DROP COLUMN IF EXISTS token_version,
+    ALTER COLUMN encrypted_private_key SET NOT NULL;
@@ -0,0 +1,5 @@
+-- Tokens carry the version they were issued under, which a password change
+-- increments. The private key is left out once its agent takes it over.
+ALTER TABLE auths
+    ADD COLUMN IF NOT EXISTS token_version INTEGER NOT NULL DEFAULT 0,
+    ALTER COLUMN encrypted_private_key DROP NOT NULL;
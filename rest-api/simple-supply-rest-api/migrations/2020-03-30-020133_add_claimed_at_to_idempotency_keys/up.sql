@@ -0,0 +1,5 @@
+-- When the request holding a key claimed it, so that a claim left behind by
+-- a request which never finished can be taken over
+ALTER TABLE idempotency_keys ADD COLUMN IF NOT EXISTS claimed_at BIGINT;
+UPDATE idempotency_keys SET claimed_at = created_at;
+ALTER TABLE idempotency_keys ALTER COLUMN claimed_at SET NOT NULL;
@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use actix_service::{Service, Transform};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use chrono::Utc;
+use futures::future::{err, ok, FutureExt, LocalBoxFuture, Ready};
+use jsonwebtoken::errors::Result as TokenResult;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+
+/// How long an issued token stays valid, in seconds
+const TOKEN_LIFETIME: i64 = 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    // The public key of the authenticated agent
+    sub: String,
+    iat: i64,
+    exp: i64,
+    // The token version of the agent when this was issued
+    #[serde(default)]
+    ver: i32,
+}
+
+pub fn generate_auth_token(secret: &str, public_key: &str, version: i32) -> TokenResult<String> {
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: public_key.to_string(),
+        iat: now,
+        exp: now + TOKEN_LIFETIME,
+        ver: version,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+/// Checks the signature and expiry of a token and returns the public key and
+/// token version it was issued for
+pub fn verify_auth_token(secret: &str, token: &str) -> TokenResult<(String, i32)> {
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )?;
+    Ok((data.claims.sub, data.claims.ver))
+}
+
+/// The agent whose token authorized the request. Only available in handlers
+/// wrapped with the `Authentication` middleware.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedAgent {
+    pub public_key: String,
+}
+
+impl FromRequest for AuthenticatedAgent {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.extensions().get::<AuthenticatedAgent>() {
+            Some(agent) => ok(agent.clone()),
+            None => err(ApiError::Unauthorized("Authorization required".to_string()).into()),
+        }
+    }
+}
+
+/// Looks up the current token version of an agent, or `None` if it has no
+/// credentials
+type TokenVersions = Rc<dyn Fn(String) -> LocalBoxFuture<'static, Result<Option<i32>, ApiError>>>;
+
+/// Middleware which rejects requests without a valid `Authorization` header
+/// and makes the caller available to handlers as an `AuthenticatedAgent`.
+/// Tokens issued before the agent last changed its password are rejected
+/// too, which `versions` tells apart.
+#[derive(Clone)]
+pub struct Authentication {
+    secret: Rc<String>,
+    versions: TokenVersions,
+}
+
+impl Authentication {
+    pub fn new<F, V>(secret: &str, versions: F) -> Self
+    where
+        F: Fn(String) -> V + 'static,
+        V: Future<Output = Result<Option<i32>, ApiError>> + 'static,
+    {
+        Authentication {
+            secret: Rc::new(secret.to_string()),
+            versions: Rc::new(move |public_key| versions(public_key).boxed_local()),
+        }
+    }
+}
+
+impl<S, B> Transform<S> for Authentication
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AuthenticationMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AuthenticationMiddleware {
+            service: Rc::new(RefCell::new(service)),
+            secret: self.secret.clone(),
+            versions: self.versions.clone(),
+        })
+    }
+}
+
+pub struct AuthenticationMiddleware<S> {
+    // Shared with the future of each request, which calls it once the token
+    // version has been looked up
+    service: Rc<RefCell<S>>,
+    secret: Rc<String>,
+    versions: TokenVersions,
+}
+
+impl<S, B> Service for AuthenticationMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let claims = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim_start_matches("Bearer ").trim())
+            .and_then(|token| verify_auth_token(&self.secret, token).ok());
+        let service = self.service.clone();
+        let versions = self.versions.clone();
+
+        Box::pin(async move {
+            let public_key = match claims {
+                Some((public_key, version)) => match versions(public_key.clone()).await {
+                    Ok(Some(current)) if current == version => Ok(public_key),
+                    Ok(_) => Err(ApiError::Unauthorized(
+                        "Invalid authorization token".to_string(),
+                    )),
+                    Err(err) => Err(err),
+                },
+                None => Err(ApiError::Unauthorized(
+                    "Invalid authorization token".to_string(),
+                )),
+            };
+
+            match public_key {
+                Ok(public_key) => {
+                    req.extensions_mut()
+                        .insert(AuthenticatedAgent { public_key });
+                    let response = service.borrow_mut().call(req);
+                    response.await
+                }
+                // Answered here rather than failed, so that outer middleware
+                // still sees the matched request
+                Err(err) => Ok(req.error_response(err)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App, HttpResponse};
+
+    const SECRET: &str = "authentication-test";
+    const AGENT: &str = "authentication-test-agent";
+
+    /// Sends a request with `token` through the middleware, with the agent
+    /// at token version 1
+    async fn status(token: Option<String>) -> StatusCode {
+        let authentication = Authentication::new(SECRET, |public_key| {
+            ok(if public_key == AGENT { Some(1) } else { None })
+        });
+        let mut app = test::init_service(
+            App::new().service(web::resource("/").wrap(authentication).to(
+                |agent: AuthenticatedAgent| {
+                    assert_eq!(agent.public_key, AGENT);
+                    HttpResponse::Ok()
+                },
+            )),
+        )
+        .await;
+        let mut request = test::TestRequest::get().uri("/");
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        test::call_service(&mut app, request.to_request())
+            .await
+            .status()
+    }
+
+    #[test]
+    fn tokens_round_trip() {
+        let token = generate_auth_token(SECRET, AGENT, 3).unwrap();
+        assert_eq!(
+            verify_auth_token(SECRET, &token).unwrap(),
+            (AGENT.to_string(), 3)
+        );
+        assert!(verify_auth_token("other secret", &token).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn current_tokens_are_accepted() {
+        let token = generate_auth_token(SECRET, AGENT, 1).unwrap();
+        assert_eq!(status(Some(token)).await, StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn tokens_from_before_a_password_change_are_rejected() {
+        let token = generate_auth_token(SECRET, AGENT, 0).unwrap();
+        assert_eq!(status(Some(token)).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_rt::test]
+    async fn tokens_of_agents_without_credentials_are_rejected() {
+        let token = generate_auth_token(SECRET, "unknown-agent", 1).unwrap();
+        assert_eq!(status(Some(token)).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_rt::test]
+    async fn missing_or_forged_tokens_are_rejected() {
+        assert_eq!(status(None).await, StatusCode::UNAUTHORIZED);
+        let forged = generate_auth_token("other secret", AGENT, 1).unwrap();
+        assert_eq!(status(Some(forged)).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader, BatchList};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use sawtooth_sdk::signing::secp256k1::Secp256k1PublicKey;
+use sawtooth_sdk::signing::{create_context, Context};
+use simple_supply_tp::addresser;
+
+use crate::transaction_creation::sha512;
+
+#[derive(Debug)]
+pub enum BatchValidationError {
+    Empty,
+    MalformedHeader(String),
+    InvalidSignature(String),
+    WrongFamily(String),
+    Mismatch(String),
+}
+
+impl fmt::Display for BatchValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchValidationError::Empty => write!(f, "no batches or transactions were submitted"),
+            BatchValidationError::MalformedHeader(id) => write!(f, "malformed header: {}", id),
+            BatchValidationError::InvalidSignature(id) => write!(f, "invalid signature: {}", id),
+            BatchValidationError::WrongFamily(id) => {
+                write!(
+                    f,
+                    "transaction is not for {}: {}",
+                    addresser::FAMILY_NAME,
+                    id
+                )
+            }
+            BatchValidationError::Mismatch(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BatchValidationError {}
+
+/// Checks that a client-signed batch list only contains well formed
+/// simple_supply transactions before it is forwarded to the validator
+pub fn validate_batch_list(batch_list: &BatchList) -> Result<(), BatchValidationError> {
+    if batch_list.get_batches().is_empty() {
+        return Err(BatchValidationError::Empty);
+    }
+    let context = create_context("secp256k1").expect("secp256k1 is always available");
+    batch_list
+        .get_batches()
+        .iter()
+        .try_for_each(|batch| validate_batch(&*context, batch))
+}
+
+fn validate_batch(context: &dyn Context, batch: &Batch) -> Result<(), BatchValidationError> {
+    let batch_id = batch.get_header_signature();
+    let header: BatchHeader = protobuf::parse_from_bytes(batch.get_header())
+        .map_err(|_| BatchValidationError::MalformedHeader(batch_id.to_string()))?;
+    verify_signature(
+        context,
+        batch_id,
+        batch.get_header(),
+        header.get_signer_public_key(),
+    )?;
+
+    let transactions = batch.get_transactions();
+    if transactions.is_empty() {
+        return Err(BatchValidationError::Empty);
+    }
+    let transaction_ids: Vec<&str> = transactions
+        .iter()
+        .map(|transaction| transaction.get_header_signature())
+        .collect();
+    if header.get_transaction_ids() != transaction_ids.as_slice() {
+        return Err(BatchValidationError::Mismatch(format!(
+            "transaction ids in the header of batch {} do not match its transactions",
+            batch_id
+        )));
+    }
+
+    transactions.iter().try_for_each(|transaction| {
+        validate_transaction(context, transaction, header.get_signer_public_key())
+    })
+}
+
+fn validate_transaction(
+    context: &dyn Context,
+    transaction: &Transaction,
+    batcher_public_key: &str,
+) -> Result<(), BatchValidationError> {
+    let transaction_id = transaction.get_header_signature();
+    let header: TransactionHeader = protobuf::parse_from_bytes(transaction.get_header())
+        .map_err(|_| BatchValidationError::MalformedHeader(transaction_id.to_string()))?;
+    verify_signature(
+        context,
+        transaction_id,
+        transaction.get_header(),
+        header.get_signer_public_key(),
+    )?;
+
+    if header.get_family_name() != addresser::FAMILY_NAME
+        || header.get_family_version() != addresser::FAMILY_VERSION
+    {
+        return Err(BatchValidationError::WrongFamily(
+            transaction_id.to_string(),
+        ));
+    }
+    if header.get_batcher_public_key() != batcher_public_key {
+        return Err(BatchValidationError::Mismatch(format!(
+            "transaction {} was not made for the batch signer",
+            transaction_id
+        )));
+    }
+    if header.get_payload_sha512() != sha512(transaction.get_payload()) {
+        return Err(BatchValidationError::Mismatch(format!(
+            "payload hash of transaction {} does not match",
+            transaction_id
+        )));
+    }
+    Ok(())
+}
+
+fn verify_signature(
+    context: &dyn Context,
+    signature: &str,
+    header: &[u8],
+    public_key: &str,
+) -> Result<(), BatchValidationError> {
+    let public_key = Secp256k1PublicKey::from_hex(public_key)
+        .map_err(|_| BatchValidationError::InvalidSignature(signature.to_string()))?;
+    match context.verify(signature, header, &public_key) {
+        Ok(true) => Ok(()),
+        _ => Err(BatchValidationError::InvalidSignature(
+            signature.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction_creation::make_create_agent_transaction;
+    use protobuf::{Message, RepeatedField};
+    use sawtooth_sdk::signing::{PrivateKey, Signer};
+
+    struct Keys {
+        context: Box<dyn Context>,
+        transaction_key: Box<dyn PrivateKey>,
+        batch_key: Box<dyn PrivateKey>,
+    }
+
+    impl Keys {
+        fn new() -> Keys {
+            let context = create_context("secp256k1").unwrap();
+            let transaction_key = context.new_random_private_key().unwrap();
+            let batch_key = context.new_random_private_key().unwrap();
+            Keys {
+                context,
+                transaction_key,
+                batch_key,
+            }
+        }
+
+        fn transaction_signer(&self) -> Signer<'_> {
+            Signer::new(&*self.context, &*self.transaction_key)
+        }
+
+        fn batch_signer(&self) -> Signer<'_> {
+            Signer::new(&*self.context, &*self.batch_key)
+        }
+
+        fn create_agent(&self, name: &str) -> Batch {
+            make_create_agent_transaction(self.transaction_signer(), self.batch_signer(), name, 0)
+        }
+    }
+
+    fn batch_list(batches: Vec<Batch>) -> BatchList {
+        BatchList {
+            batches: RepeatedField::from_vec(batches),
+            ..Default::default()
+        }
+    }
+
+    /// Signs a batch around `transactions` as they are, however they were
+    /// made
+    fn sign_batch(batch_signer: &Signer, transactions: Vec<Transaction>) -> Batch {
+        let header = BatchHeader {
+            signer_public_key: batch_signer.get_public_key().unwrap().as_hex(),
+            transaction_ids: transactions
+                .iter()
+                .map(|transaction| transaction.get_header_signature().to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let header_bytes = header.write_to_bytes().unwrap();
+        Batch {
+            header_signature: batch_signer.sign(&header_bytes).unwrap(),
+            header: header_bytes,
+            transactions: RepeatedField::from_vec(transactions),
+            ..Default::default()
+        }
+    }
+
+    fn sign_transaction(signer: &Signer, header: TransactionHeader, payload: &[u8]) -> Transaction {
+        let header_bytes = header.write_to_bytes().unwrap();
+        Transaction {
+            header_signature: signer.sign(&header_bytes).unwrap(),
+            header: header_bytes,
+            payload: payload.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accepts_signed_batches() {
+        let keys = Keys::new();
+        let batches = vec![keys.create_agent("alice"), keys.create_agent("bob")];
+        validate_batch_list(&batch_list(batches)).unwrap();
+    }
+
+    #[test]
+    fn rejects_empty_lists_and_batches() {
+        let keys = Keys::new();
+        match validate_batch_list(&batch_list(Vec::new())) {
+            Err(BatchValidationError::Empty) => (),
+            other => panic!("expected Empty, got {:?}", other),
+        }
+        let batch = sign_batch(&keys.batch_signer(), Vec::new());
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::Empty) => (),
+            other => panic!("expected Empty, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        let keys = Keys::new();
+
+        let mut batch = keys.create_agent("alice");
+        batch.set_header_signature(keys.create_agent("alice").take_header_signature());
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::InvalidSignature(_)) => (),
+            other => panic!("expected InvalidSignature, got {:?}", other),
+        }
+
+        // A transaction header swapped for one its signature wasn't made for
+        let mut transaction = keys.create_agent("alice").take_transactions().remove(0);
+        let other = keys.create_agent("bob").take_transactions().remove(0);
+        transaction.set_header(other.get_header().to_vec());
+        let batch = sign_batch(&keys.batch_signer(), vec![transaction]);
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::InvalidSignature(_)) => (),
+            other => panic!("expected InvalidSignature, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let keys = Keys::new();
+        let mut batch = keys.create_agent("alice");
+        batch.set_header(vec![0xff; 8]);
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::MalformedHeader(_)) => (),
+            other => panic!("expected MalformedHeader, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_other_families() {
+        let keys = Keys::new();
+        let transaction_signer = keys.transaction_signer();
+        let batch_signer = keys.batch_signer();
+        let payload = b"intkey payload";
+        let header = TransactionHeader {
+            family_name: "intkey".to_string(),
+            family_version: "1.0".to_string(),
+            signer_public_key: transaction_signer.get_public_key().unwrap().as_hex(),
+            batcher_public_key: batch_signer.get_public_key().unwrap().as_hex(),
+            payload_sha512: sha512(payload),
+            ..Default::default()
+        };
+        let transaction = sign_transaction(&transaction_signer, header, payload);
+        let batch = sign_batch(&batch_signer, vec![transaction]);
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::WrongFamily(_)) => (),
+            other => panic!("expected WrongFamily, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_mismatches() {
+        let keys = Keys::new();
+        let other_keys = Keys::new();
+
+        // Ids listed in the batch header must be its transactions
+        let mut batch = keys.create_agent("alice");
+        batch.set_transactions(keys.create_agent("bob").take_transactions());
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::Mismatch(_)) => (),
+            other => panic!("expected Mismatch, got {:?}", other),
+        }
+
+        // Transactions name the key of the batch they belong in
+        let transactions = keys.create_agent("alice").take_transactions().into_vec();
+        let batch = sign_batch(&other_keys.batch_signer(), transactions);
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::Mismatch(_)) => (),
+            other => panic!("expected Mismatch, got {:?}", other),
+        }
+
+        // The payload must be the one the header was signed over
+        let mut transaction = keys.create_agent("alice").take_transactions().remove(0);
+        transaction.set_payload(b"tampered".to_vec());
+        let batch = sign_batch(&keys.batch_signer(), vec![transaction]);
+        match validate_batch_list(&batch_list(vec![batch])) {
+            Err(BatchValidationError::Mismatch(_)) => (),
+            other => panic!("expected Mismatch, got {:?}", other),
+        }
+    }
+}
@@ -1,7 +1,49 @@
+use crate::error::ApiError;
 use crate::model::*;
+use actix_web::web;
+use diesel::dsl::sql;
 use diesel::insert_into;
 use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::result::QueryResult;
+use diesel::sql_types::{BigInt, Varchar};
+
+/// `end_block_num` of rows which are still current
+pub const MAX_BLOCK_NUMBER: i64 = i64::MAX;
+
+// Flags the recalled record and every assembly it is currently part of,
+// walking up the `assembly` column of the current record rows
+const RECALL_LINEAGE_QUERY: &str = "
+    WITH RECURSIVE lineage(record_id) AS (
+        SELECT CAST($1 AS VARCHAR)
+        UNION
+        SELECT records.assembly
+        FROM records JOIN lineage ON records.record_id = lineage.record_id
+        WHERE records.end_block_num = $3
+          AND records.assembly IS NOT NULL
+          AND records.assembly <> ''
+    )
+    INSERT INTO record_recalls
+        (record_id, source_record_id, timestamp, start_block_num, end_block_num)
+    SELECT record_id, $1, $2, $4, $3 FROM lineage";
+
+/// Runs `query` with a pooled connection on the blocking thread pool, so that
+/// waiting on the database doesn't hold up the other requests of a worker
+pub async fn with_connection<F, T>(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+    query: F,
+) -> Result<T, ApiError>
+where
+    F: FnOnce(&PgConnection) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let pool = pool.clone();
+    Ok(web::block(move || {
+        let conn = pool.get()?;
+        query(&conn)
+    })
+    .await?)
+}
 
 pub fn insert_auth(cn: &PgConnection, auth: &NewAuth) -> QueryResult<String> {
     use crate::schema::auths::dsl;
@@ -11,6 +53,654 @@ pub fn insert_auth(cn: &PgConnection, auth: &NewAuth) -> QueryResult<String> {
         .get_result(cn)
 }
 
+pub fn fetch_auth(cn: &PgConnection, public_key: &str) -> QueryResult<Option<Auth>> {
+    use crate::schema::auths::dsl;
+    dsl::auths
+        .filter(dsl::public_key.eq(public_key))
+        .first(cn)
+        .optional()
+}
+
+/// Replaces the password hash, encrypted private key and token version of
+/// an agent
+pub fn update_auth(cn: &PgConnection, auth: &Auth) -> QueryResult<usize> {
+    use crate::schema::auths::dsl;
+    diesel::update(dsl::auths.filter(dsl::public_key.eq(&auth.public_key)))
+        .set((
+            dsl::hashed_password.eq(&auth.hashed_password),
+            dsl::encrypted_private_key.eq(&auth.encrypted_private_key),
+            dsl::token_version.eq(auth.token_version),
+        ))
+        .execute(cn)
+}
+
+/// The version of the tokens an agent may still use, or `None` if it has no
+/// credentials
+pub fn fetch_token_version(cn: &PgConnection, public_key: &str) -> QueryResult<Option<i32>> {
+    use crate::schema::auths::dsl;
+    dsl::auths
+        .filter(dsl::public_key.eq(public_key))
+        .select(dsl::token_version)
+        .first(cn)
+        .optional()
+}
+
+/// Claims an idempotency key for a request, returning false if it was
+/// already claimed
+pub fn insert_idempotency_key(cn: &PgConnection, key: &NewIdempotencyKey) -> QueryResult<bool> {
+    use crate::schema::idempotency_keys::dsl;
+    insert_into(dsl::idempotency_keys)
+        .values(key)
+        .on_conflict_do_nothing()
+        .execute(cn)
+        .map(|inserted| inserted == 1)
+}
+
+pub fn fetch_idempotency_key(
+    cn: &PgConnection,
+    idempotency_key: &str,
+    agent: &str,
+) -> QueryResult<Option<IdempotencyKey>> {
+    use crate::schema::idempotency_keys::dsl;
+    dsl::idempotency_keys
+        .filter(dsl::idempotency_key.eq(idempotency_key))
+        .filter(dsl::agent.eq(agent))
+        .first(cn)
+        .optional()
+}
+
+/// Moves the claim on an idempotency key whose request never finished over
+/// to another request, returning false if someone else took it over first
+pub fn take_over_idempotency_key(
+    cn: &PgConnection,
+    idempotency_key: &str,
+    agent: &str,
+    stale_claimed_at: i64,
+    claimed_at: i64,
+) -> QueryResult<bool> {
+    use crate::schema::idempotency_keys::dsl;
+    diesel::update(
+        dsl::idempotency_keys
+            .filter(dsl::idempotency_key.eq(idempotency_key))
+            .filter(dsl::agent.eq(agent))
+            .filter(dsl::status_code.is_null())
+            .filter(dsl::claimed_at.eq(stale_claimed_at)),
+    )
+    .set(dsl::claimed_at.eq(claimed_at))
+    .execute(cn)
+    .map(|updated| updated == 1)
+}
+
+/// Stores the response to the request an idempotency key was claimed for
+pub fn complete_idempotency_key(
+    cn: &PgConnection,
+    idempotency_key: &str,
+    agent: &str,
+    status_code: i32,
+    content_type: Option<&str>,
+    response_body: &[u8],
+) -> QueryResult<usize> {
+    use crate::schema::idempotency_keys::dsl;
+    diesel::update(
+        dsl::idempotency_keys
+            .filter(dsl::idempotency_key.eq(idempotency_key))
+            .filter(dsl::agent.eq(agent)),
+    )
+    .set((
+        dsl::status_code.eq(status_code),
+        dsl::content_type.eq(content_type),
+        dsl::response_body.eq(response_body),
+    ))
+    .execute(cn)
+}
+
+pub fn delete_idempotency_key(
+    cn: &PgConnection,
+    idempotency_key: &str,
+    agent: &str,
+) -> QueryResult<usize> {
+    use crate::schema::idempotency_keys::dsl;
+    diesel::delete(
+        dsl::idempotency_keys
+            .filter(dsl::idempotency_key.eq(idempotency_key))
+            .filter(dsl::agent.eq(agent)),
+    )
+    .execute(cn)
+}
+
+/// Forgets idempotency keys claimed before `created_before`
+pub fn delete_expired_idempotency_keys(
+    cn: &PgConnection,
+    created_before: i64,
+) -> QueryResult<usize> {
+    use crate::schema::idempotency_keys::dsl;
+    diesel::delete(dsl::idempotency_keys.filter(dsl::created_at.lt(created_before))).execute(cn)
+}
+
+pub fn fetch_block_id(cn: &PgConnection, block_num: i64) -> QueryResult<Option<String>> {
+    use crate::schema::blocks::dsl;
+    dsl::blocks
+        .filter(dsl::block_num.eq(block_num))
+        .select(dsl::block_id)
+        .first::<Option<String>>(cn)
+        .optional()
+        .map(Option::flatten)
+}
+
+/// The ids of the `count` latest stored blocks, newest first
+pub fn fetch_latest_block_ids(cn: &PgConnection, count: i64) -> QueryResult<Vec<String>> {
+    use crate::schema::blocks::dsl;
+    dsl::blocks
+        .filter(dsl::block_id.is_not_null())
+        .order(dsl::block_num.desc())
+        .limit(count)
+        .select(dsl::block_id)
+        .load::<Option<String>>(cn)
+        .map(|ids| ids.into_iter().flatten().collect())
+}
+
+/// The timestamp of the latest block before `block_num`
+pub fn fetch_block_timestamp_before(cn: &PgConnection, block_num: i64) -> QueryResult<Option<i64>> {
+    use crate::schema::blocks::dsl;
+    use diesel::expression::dsl::max;
+    dsl::blocks
+        .filter(dsl::block_num.lt(block_num))
+        .select(max(dsl::timestamp))
+        .first(cn)
+}
+
+/// The most recent block committed at or before `timestamp`
+pub fn fetch_block_num_at(cn: &PgConnection, timestamp: i64) -> QueryResult<Option<i64>> {
+    use crate::schema::blocks::dsl;
+    dsl::blocks
+        .filter(dsl::timestamp.le(timestamp))
+        .order(dsl::block_num.desc())
+        .select(dsl::block_num)
+        .first(cn)
+        .optional()
+}
+
+pub fn insert_block(cn: &PgConnection, block: &NewBlock) -> QueryResult<usize> {
+    use crate::schema::blocks::dsl;
+    insert_into(dsl::blocks).values(block).execute(cn)
+}
+
+/// Removes everything written at or after `block_num`, and makes the rows
+/// which that fork had superseded current again
+pub fn drop_fork(cn: &PgConnection, block_num: i64) -> QueryResult<()> {
+    use crate::schema::{agents, blocks, record_locations, record_owners, record_recalls, records};
+
+    macro_rules! drop_fork_from {
+        ($table:ident) => {
+            diesel::delete($table::table.filter($table::start_block_num.ge(block_num)))
+                .execute(cn)?;
+            diesel::update($table::table.filter($table::end_block_num.ge(block_num)))
+                .set($table::end_block_num.eq(MAX_BLOCK_NUMBER))
+                .execute(cn)?;
+        };
+    }
+
+    drop_fork_from!(agents);
+    drop_fork_from!(records);
+    drop_fork_from!(record_owners);
+    drop_fork_from!(record_locations);
+    drop_fork_from!(record_recalls);
+
+    diesel::delete(blocks::table.filter(blocks::block_num.ge(block_num))).execute(cn)?;
+    Ok(())
+}
+
+pub fn insert_agent(cn: &PgConnection, agent: &NewAgent) -> QueryResult<usize> {
+    use crate::schema::agents::dsl;
+    diesel::update(
+        dsl::agents
+            .filter(dsl::public_key.eq(&agent.public_key))
+            .filter(dsl::end_block_num.eq(MAX_BLOCK_NUMBER)),
+    )
+    .set(dsl::end_block_num.eq(agent.start_block_num))
+    .execute(cn)?;
+    insert_into(dsl::agents).values(agent).execute(cn)
+}
+
+// Restricts a boxed query on `$table` to the rows valid at `$at_block`, or to
+// the current rows when it is `None`
+macro_rules! valid_at {
+    ($query:expr, $table:ident, $at_block:expr) => {
+        match $at_block {
+            Some(block_num) => $query
+                .filter($table::start_block_num.le(block_num))
+                .filter($table::end_block_num.gt(block_num)),
+            None => $query.filter($table::end_block_num.eq(MAX_BLOCK_NUMBER)),
+        }
+    };
+}
+
+/// Which page of a list to load. Lists are ordered by their natural key, or
+/// by `sort` and then their key, and `start` is the key of the first row on
+/// the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paging {
+    pub start: Option<String>,
+    pub limit: i64,
+    pub descending: bool,
+    pub sort: Option<Sort>,
+}
+
+/// A field to order a list by before its key, holding the value of the first
+/// row on the page unless it starts at the beginning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sort {
+    Name(Option<String>),
+    LastUpdated(Option<i64>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgentFilter {
+    /// Case-insensitive substring of the agent's name
+    pub name: Option<String>,
+    pub updated_since: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordFilter {
+    /// Public key of the current owner
+    pub owner: Option<String>,
+    /// Only records whose latest location is inside the box
+    pub bounding_box: Option<BoundingBox>,
+    pub updated_since: Option<i64>,
+}
+
+/// Coordinates are in millionths of a degree, bounds inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_latitude: i64,
+    pub min_longitude: i64,
+    pub max_latitude: i64,
+    pub max_longitude: i64,
+}
+
+// Orders a boxed query by `$key` and restricts it to the page described by
+// `$paging`. One row past the page is loaded so that callers can tell whether
+// there is a next page.
+macro_rules! page {
+    ($query:expr, $key:expr, $paging:expr) => {{
+        let paging: &Paging = $paging;
+        let query = match (&paging.start, paging.descending) {
+            (Some(start), false) => $query.filter($key.ge(start.clone())),
+            (Some(start), true) => $query.filter($key.le(start.clone())),
+            (None, _) => $query,
+        };
+        let query = if paging.descending {
+            query.order($key.desc())
+        } else {
+            query.order($key.asc())
+        };
+        query.limit(paging.limit + 1)
+    }};
+}
+
+// Like `page!`, but orders by `$column` first. `$value` is the column's value
+// at the first row of the page, which is only found by its key among rows
+// with the same value.
+macro_rules! page_by {
+    ($query:expr, $column:expr, $value:expr, $key:expr, $paging:expr) => {{
+        let paging: &Paging = $paging;
+        let query = match ($value, &paging.start) {
+            (Some(value), Some(start)) if paging.descending => $query.filter(
+                $column
+                    .lt(value.clone())
+                    .or($column.eq(value.clone()).and($key.le(start.clone()))),
+            ),
+            (Some(value), Some(start)) => $query.filter(
+                $column
+                    .gt(value.clone())
+                    .or($column.eq(value.clone()).and($key.ge(start.clone()))),
+            ),
+            _ => $query,
+        };
+        let query = if paging.descending {
+            query.order(($column.desc(), $key.desc()))
+        } else {
+            query.order(($column.asc(), $key.asc()))
+        };
+        query.limit(paging.limit + 1)
+    }};
+}
+
+/// Escapes the characters `LIKE` treats specially, so that `value` only
+/// matches itself
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub fn fetch_agents(
+    cn: &PgConnection,
+    at_block: Option<i64>,
+    filter: &AgentFilter,
+    paging: &Paging,
+) -> QueryResult<Vec<Agent>> {
+    use crate::schema::agents;
+    let mut query = valid_at!(agents::table.into_boxed(), agents, at_block);
+    if let Some(name) = &filter.name {
+        query = query.filter(agents::name.ilike(format!("%{}%", escape_like(name))));
+    }
+    if let Some(updated_since) = filter.updated_since {
+        query = query.filter(agents::timestamp.ge(updated_since));
+    }
+    match &paging.sort {
+        None => page!(query, agents::public_key, paging).load(cn),
+        Some(Sort::Name(value)) => {
+            page_by!(query, agents::name, value, agents::public_key, paging).load(cn)
+        }
+        Some(Sort::LastUpdated(value)) => {
+            page_by!(query, agents::timestamp, value, agents::public_key, paging).load(cn)
+        }
+    }
+}
+
+pub fn fetch_agent(
+    cn: &PgConnection,
+    public_key: &str,
+    at_block: Option<i64>,
+) -> QueryResult<Option<Agent>> {
+    use crate::schema::agents;
+    valid_at!(agents::table.into_boxed(), agents, at_block)
+        .filter(agents::public_key.eq(public_key))
+        .first(cn)
+        .optional()
+}
+
+/// Stores a new version of a record. The owner and location histories are
+/// always written out in full, since they are small and append-only.
+pub fn insert_record(
+    cn: &PgConnection,
+    record: &NewRecord,
+    owners: &[NewRecordOwner],
+    locations: &[NewRecordLocation],
+) -> QueryResult<()> {
+    use crate::schema::{record_locations, record_owners, records};
+
+    diesel::update(
+        records::table
+            .filter(records::record_id.eq(&record.record_id))
+            .filter(records::end_block_num.eq(MAX_BLOCK_NUMBER)),
+    )
+    .set(records::end_block_num.eq(record.start_block_num))
+    .execute(cn)?;
+    insert_into(records::table).values(record).execute(cn)?;
+
+    diesel::update(
+        record_owners::table
+            .filter(record_owners::record_id.eq(&record.record_id))
+            .filter(record_owners::end_block_num.eq(MAX_BLOCK_NUMBER)),
+    )
+    .set(record_owners::end_block_num.eq(record.start_block_num))
+    .execute(cn)?;
+    insert_into(record_owners::table)
+        .values(owners)
+        .execute(cn)?;
+
+    diesel::update(
+        record_locations::table
+            .filter(record_locations::record_id.eq(&record.record_id))
+            .filter(record_locations::end_block_num.eq(MAX_BLOCK_NUMBER)),
+    )
+    .set(record_locations::end_block_num.eq(record.start_block_num))
+    .execute(cn)?;
+    insert_into(record_locations::table)
+        .values(locations)
+        .execute(cn)?;
+
+    Ok(())
+}
+
+pub fn fetch_records(
+    cn: &PgConnection,
+    at_block: Option<i64>,
+    filter: &RecordFilter,
+    paging: &Paging,
+) -> QueryResult<Vec<Record>> {
+    use crate::schema::records;
+    let mut query = valid_at!(records::table.into_boxed(), records, at_block);
+    if let Some(owner) = &filter.owner {
+        query = query.filter(records::owner.eq(owner.clone()));
+    }
+    if let Some(bounding_box) = filter.bounding_box {
+        query = query
+            .filter(records::latitude.ge(bounding_box.min_latitude))
+            .filter(records::latitude.le(bounding_box.max_latitude))
+            .filter(records::longitude.ge(bounding_box.min_longitude))
+            .filter(records::longitude.le(bounding_box.max_longitude));
+    }
+    if let Some(updated_since) = filter.updated_since {
+        query = query.filter(records::last_updated.ge(updated_since));
+    }
+    match &paging.sort {
+        None => page!(query, records::record_id, paging).load(cn),
+        // Records without any history sort as the oldest
+        Some(Sort::LastUpdated(value)) => page_by!(
+            query,
+            sql::<BigInt>("COALESCE(records.last_updated, 0)"),
+            value,
+            records::record_id,
+            paging
+        )
+        .load(cn),
+        Some(sort) => Err(diesel::result::Error::QueryBuilderError(
+            format!("records can't be sorted by {:?}", sort).into(),
+        )),
+    }
+}
+
+pub fn fetch_record(
+    cn: &PgConnection,
+    record_id: &str,
+    at_block: Option<i64>,
+) -> QueryResult<Option<Record>> {
+    use crate::schema::records;
+    valid_at!(records::table.into_boxed(), records, at_block)
+        .filter(records::record_id.eq(record_id))
+        .first(cn)
+        .optional()
+}
+
+/// Owner history of the given records, oldest first
+pub fn fetch_record_owners(
+    cn: &PgConnection,
+    record_ids: &[String],
+    at_block: Option<i64>,
+) -> QueryResult<Vec<RecordOwner>> {
+    use crate::schema::record_owners;
+    valid_at!(record_owners::table.into_boxed(), record_owners, at_block)
+        .filter(record_owners::record_id.eq_any(record_ids))
+        .order((record_owners::timestamp.asc(), record_owners::id.asc()))
+        .load(cn)
+}
+
+/// Location history of the given records, oldest first
+pub fn fetch_record_locations(
+    cn: &PgConnection,
+    record_ids: &[String],
+    at_block: Option<i64>,
+) -> QueryResult<Vec<RecordLocation>> {
+    use crate::schema::record_locations;
+    valid_at!(
+        record_locations::table.into_boxed(),
+        record_locations,
+        at_block
+    )
+    .filter(record_locations::record_id.eq_any(record_ids))
+    .order((
+        record_locations::timestamp.asc(),
+        record_locations::id.asc(),
+    ))
+    .load(cn)
+}
+
+/// Pairs of (assembly, component) for components assembled into any of the
+/// given records
+pub fn fetch_record_components(
+    cn: &PgConnection,
+    record_ids: &[String],
+    at_block: Option<i64>,
+) -> QueryResult<Vec<(String, String)>> {
+    use crate::schema::records;
+    valid_at!(records::table.into_boxed(), records, at_block)
+        .filter(records::assembly.eq_any(record_ids))
+        .order(records::record_id.asc())
+        .select((records::assembly, records::record_id))
+        .load::<(Option<String>, String)>(cn)
+        .map(|pairs| {
+            pairs
+                .into_iter()
+                .filter_map(|(assembly, record_id)| assembly.map(|assembly| (assembly, record_id)))
+                .collect()
+        })
+}
+
+/// The subset of the given records which are recalled
+pub fn fetch_recalled(
+    cn: &PgConnection,
+    record_ids: &[String],
+    at_block: Option<i64>,
+) -> QueryResult<Vec<String>> {
+    use crate::schema::record_recalls;
+    valid_at!(record_recalls::table.into_boxed(), record_recalls, at_block)
+        .filter(record_recalls::record_id.eq_any(record_ids))
+        .select(record_recalls::record_id)
+        .distinct()
+        .load(cn)
+}
+
+/// Limits an export to rows stamped within a time range and related to an
+/// agent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub owner: Option<String>,
+}
+
+// Restricts a boxed query on `$table` to the export's time range on
+// `$timestamp`, and orders it for reading in chunks of `$limit` rows after
+// `$after_id`
+macro_rules! export_chunk {
+    ($query:expr, $table:ident, $timestamp:expr, $filter:expr, $after_id:expr, $limit:expr) => {{
+        let mut query = $query
+            .filter($table::end_block_num.eq(MAX_BLOCK_NUMBER))
+            .filter($table::id.gt($after_id));
+        if let Some(since) = $filter.since {
+            query = query.filter($timestamp.ge(since));
+        }
+        if let Some(until) = $filter.until {
+            query = query.filter($timestamp.lt(until));
+        }
+        query.order($table::id.asc()).limit($limit)
+    }};
+}
+
+pub fn export_agents(
+    cn: &PgConnection,
+    filter: &ExportFilter,
+    after_id: i64,
+    limit: i64,
+) -> QueryResult<Vec<Agent>> {
+    use crate::schema::agents;
+    let mut query = agents::table.into_boxed();
+    if let Some(owner) = &filter.owner {
+        query = query.filter(agents::public_key.eq(owner.clone()));
+    }
+    export_chunk!(query, agents, agents::timestamp, filter, after_id, limit).load(cn)
+}
+
+pub fn export_records(
+    cn: &PgConnection,
+    filter: &ExportFilter,
+    after_id: i64,
+    limit: i64,
+) -> QueryResult<Vec<Record>> {
+    use crate::schema::records;
+    let mut query = records::table.into_boxed();
+    if let Some(owner) = &filter.owner {
+        query = query.filter(records::owner.eq(owner.clone()));
+    }
+    export_chunk!(
+        query,
+        records,
+        records::last_updated,
+        filter,
+        after_id,
+        limit
+    )
+    .load(cn)
+}
+
+pub fn export_record_owners(
+    cn: &PgConnection,
+    filter: &ExportFilter,
+    after_id: i64,
+    limit: i64,
+) -> QueryResult<Vec<RecordOwner>> {
+    use crate::schema::record_owners;
+    let mut query = record_owners::table.into_boxed();
+    if let Some(owner) = &filter.owner {
+        query = query.filter(record_owners::agent_id.eq(owner.clone()));
+    }
+    export_chunk!(
+        query,
+        record_owners,
+        record_owners::timestamp,
+        filter,
+        after_id,
+        limit
+    )
+    .load(cn)
+}
+
+/// Locations of the records currently owned by `filter.owner`, if set
+pub fn export_record_locations(
+    cn: &PgConnection,
+    filter: &ExportFilter,
+    after_id: i64,
+    limit: i64,
+) -> QueryResult<Vec<RecordLocation>> {
+    use crate::schema::{record_locations, records};
+    let mut query = record_locations::table.into_boxed();
+    if let Some(owner) = &filter.owner {
+        let owned = records::table
+            .filter(records::owner.eq(owner.clone()))
+            .filter(records::end_block_num.eq(MAX_BLOCK_NUMBER))
+            .select(records::record_id);
+        query = query.filter(record_locations::record_id.eq_any(owned));
+    }
+    export_chunk!(
+        query,
+        record_locations,
+        record_locations::timestamp,
+        filter,
+        after_id,
+        limit
+    )
+    .load(cn)
+}
+
+pub fn insert_recall(
+    cn: &PgConnection,
+    record_id: &str,
+    timestamp: i64,
+    block_num: i64,
+) -> QueryResult<usize> {
+    diesel::sql_query(RECALL_LINEAGE_QUERY)
+        .bind::<Varchar, _>(record_id)
+        .bind::<BigInt, _>(timestamp)
+        .bind::<BigInt, _>(MAX_BLOCK_NUMBER)
+        .bind::<BigInt, _>(block_num)
+        .execute(cn)
+}
+
 // pub fn insert_logs(cn: &PgConnection, logs: &[NewLog]) -> QueryResult<Vec<i64>> {
 //     use crate::schema::logs::dsl;
 //     insert_into(dsl::logs)
@@ -36,3 +726,15 @@ pub fn insert_auth(cn: &PgConnection, auth: &NewAuth) -> QueryResult<String> {
 //     }
 //     query.order(dsl::timestamp.asc()).load(cn)
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_metacharacters_are_escaped() {
+        assert_eq!(escape_like("fish"), "fish");
+        assert_eq!(escape_like("100%_fresh"), "100\\%\\_fresh");
+        assert_eq!(escape_like("C:\\fish"), "C:\\\\fish");
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+
+use openssl::aes::{aes_ige, AesKey};
+use openssl::error::ErrorStack;
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher, Mode};
+use rustc_hex::{FromHex, ToHex};
+use sawtooth_sdk::signing::secp256k1::Secp256k1PrivateKey;
+
+/// Leading byte of private keys encrypted with AES-256-GCM
+const VERSION_AES_256_GCM: u8 = 1;
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Key that private keys were encrypted with before the ciphertext was
+/// versioned. Only used to read those old entries.
+const LEGACY_AES_KEY: &str = "ffffffffffffffffffffffffffffffff";
+const LEGACY_CIPHERTEXT_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum EncryptionError {
+    InvalidKey(String),
+    MalformedCiphertext(String),
+    CryptoError(ErrorStack),
+}
+
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncryptionError::InvalidKey(msg) => write!(f, "invalid key: {}", msg),
+            EncryptionError::MalformedCiphertext(msg) => {
+                write!(f, "malformed ciphertext: {}", msg)
+            }
+            EncryptionError::CryptoError(err) => write!(f, "encryption failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+impl From<ErrorStack> for EncryptionError {
+    fn from(err: ErrorStack) -> Self {
+        EncryptionError::CryptoError(err)
+    }
+}
+
+/// Parses the hex encoded AES-256 key the server encrypts private keys with
+pub fn parse_aes_key(hex: &str) -> Result<Vec<u8>, EncryptionError> {
+    let key: Vec<u8> = hex
+        .from_hex()
+        .map_err(|err| EncryptionError::InvalidKey(err.to_string()))?;
+    if key.len() != KEY_LEN {
+        return Err(EncryptionError::InvalidKey(format!(
+            "expected {} bytes, got {}",
+            KEY_LEN,
+            key.len()
+        )));
+    }
+    Ok(key)
+}
+
+/// Encrypts a private key with AES-256-GCM under a random nonce. The public
+/// key is authenticated along with it so that an entry can't be moved to
+/// another agent. The result is hex encoded as
+/// `version || nonce || tag || ciphertext`.
+pub fn encrypt_private_key(
+    aes_key: &[u8],
+    public_key: &str,
+    private_key: &[u8],
+) -> Result<String, EncryptionError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand_bytes(&mut nonce)?;
+    let mut tag = [0u8; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        aes_key,
+        Some(&nonce),
+        public_key.as_bytes(),
+        private_key,
+        &mut tag,
+    )?;
+
+    let mut output = Vec::with_capacity(1 + NONCE_LEN + TAG_LEN + ciphertext.len());
+    output.push(VERSION_AES_256_GCM);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&tag);
+    output.extend_from_slice(&ciphertext);
+    Ok(output.to_hex())
+}
+
+/// Decrypts a private key stored by `encrypt_private_key`, or one written
+/// before the format was versioned
+pub fn decrypt_private_key(
+    aes_key: &[u8],
+    public_key: &str,
+    encrypted_private_key: &str,
+) -> Result<Secp256k1PrivateKey, EncryptionError> {
+    let data: Vec<u8> = encrypted_private_key
+        .from_hex()
+        .map_err(|err| EncryptionError::MalformedCiphertext(err.to_string()))?;
+
+    let private_key = if data.len() == LEGACY_CIPHERTEXT_LEN {
+        decrypt_legacy(public_key, &data)?
+    } else {
+        match data.split_first() {
+            Some((&VERSION_AES_256_GCM, rest)) if rest.len() > NONCE_LEN + TAG_LEN => {
+                let (nonce, rest) = rest.split_at(NONCE_LEN);
+                let (tag, ciphertext) = rest.split_at(TAG_LEN);
+                decrypt_aead(
+                    Cipher::aes_256_gcm(),
+                    aes_key,
+                    Some(nonce),
+                    public_key.as_bytes(),
+                    ciphertext,
+                    tag,
+                )?
+            }
+            Some((version, _)) => {
+                return Err(EncryptionError::MalformedCiphertext(format!(
+                    "unsupported version {} or truncated data",
+                    version
+                )))
+            }
+            None => {
+                return Err(EncryptionError::MalformedCiphertext(
+                    "empty ciphertext".to_string(),
+                ))
+            }
+        }
+    };
+
+    Secp256k1PrivateKey::from_hex(&private_key.to_hex::<String>())
+        .map_err(|err| EncryptionError::MalformedCiphertext(err.to_string()))
+}
+
+fn decrypt_legacy(public_key: &str, ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let aes_key: Vec<u8> = LEGACY_AES_KEY.from_hex().expect("invalid legacy key");
+    let key = AesKey::new_decrypt(&aes_key)
+        .map_err(|_| EncryptionError::InvalidKey("legacy key rejected".to_string()))?;
+    let mut iv: Vec<u8> = public_key
+        .from_hex()
+        .map_err(|err| EncryptionError::InvalidKey(err.to_string()))?;
+    if iv.len() < 32 {
+        return Err(EncryptionError::InvalidKey(
+            "public key too short".to_string(),
+        ));
+    }
+
+    let mut output = [0u8; LEGACY_CIPHERTEXT_LEN];
+    aes_ige(ciphertext, &mut output, &key, &mut iv[..32], Mode::Decrypt);
+    Ok(output.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sawtooth_sdk::signing::{create_context, PrivateKey};
+
+    const AES_KEY: [u8; KEY_LEN] = [7; KEY_LEN];
+
+    /// A fresh key pair as hex public key and private key bytes
+    fn key_pair() -> (String, Vec<u8>) {
+        let context = create_context("secp256k1").unwrap();
+        let private_key = context.new_random_private_key().unwrap();
+        let public_key = context.get_public_key(&*private_key).unwrap().as_hex();
+        (public_key, private_key.as_slice().to_vec())
+    }
+
+    /// Flips a bit in byte `index` of hex encoded data
+    fn flip(hex: &str, index: usize) -> String {
+        let mut data: Vec<u8> = hex.from_hex().unwrap();
+        data[index] ^= 1;
+        data.to_hex()
+    }
+
+    #[test]
+    fn round_trip() {
+        let (public_key, private_key) = key_pair();
+        let encrypted = encrypt_private_key(&AES_KEY, &public_key, &private_key).unwrap();
+        let decrypted = decrypt_private_key(&AES_KEY, &public_key, &encrypted).unwrap();
+        assert_eq!(decrypted.as_slice(), &private_key[..]);
+
+        // Every encryption gets its own nonce
+        let again = encrypt_private_key(&AES_KEY, &public_key, &private_key).unwrap();
+        assert_ne!(encrypted, again);
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let (public_key, private_key) = key_pair();
+        let encrypted = encrypt_private_key(&AES_KEY, &public_key, &private_key).unwrap();
+
+        let nonce = 1;
+        let tag = 1 + NONCE_LEN;
+        let ciphertext = 1 + NONCE_LEN + TAG_LEN;
+        for index in &[nonce, tag, ciphertext] {
+            let tampered = flip(&encrypted, *index);
+            match decrypt_private_key(&AES_KEY, &public_key, &tampered) {
+                Err(EncryptionError::CryptoError(_)) => (),
+                other => panic!("byte {} was changed, got {:?}", index, other.map(|_| ())),
+            }
+        }
+
+        let mut wrong_key = AES_KEY;
+        wrong_key[0] ^= 1;
+        assert!(decrypt_private_key(&wrong_key, &public_key, &encrypted).is_err());
+    }
+
+    #[test]
+    fn entries_are_bound_to_their_public_key() {
+        let (public_key, private_key) = key_pair();
+        let (other_public_key, _) = key_pair();
+        let encrypted = encrypt_private_key(&AES_KEY, &public_key, &private_key).unwrap();
+        assert!(decrypt_private_key(&AES_KEY, &other_public_key, &encrypted).is_err());
+    }
+
+    #[test]
+    fn legacy_entries_decrypt() {
+        let (public_key, private_key) = key_pair();
+        let legacy_key: Vec<u8> = LEGACY_AES_KEY.from_hex().unwrap();
+        let key = AesKey::new_encrypt(&legacy_key).unwrap();
+        let mut iv: Vec<u8> = public_key.from_hex().unwrap();
+        let mut encrypted = [0u8; LEGACY_CIPHERTEXT_LEN];
+        aes_ige(
+            &private_key,
+            &mut encrypted,
+            &key,
+            &mut iv[..32],
+            Mode::Encrypt,
+        );
+
+        let decrypted =
+            decrypt_private_key(&AES_KEY, &public_key, &encrypted.to_hex::<String>()).unwrap();
+        assert_eq!(decrypted.as_slice(), &private_key[..]);
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        let (public_key, private_key) = key_pair();
+        let encrypted = encrypt_private_key(&AES_KEY, &public_key, &private_key).unwrap();
+
+        let mut unknown_version: Vec<u8> = encrypted.from_hex().unwrap();
+        unknown_version[0] = 2;
+        let truncated = &encrypted[..2 * (1 + NONCE_LEN + TAG_LEN)];
+        for data in &[
+            unknown_version.to_hex(),
+            truncated.to_string(),
+            String::new(),
+        ] {
+            match decrypt_private_key(&AES_KEY, &public_key, data) {
+                Err(EncryptionError::MalformedCiphertext(_)) => (),
+                other => panic!("{:?} was accepted: {:?}", data, other.map(|_| ())),
+            }
+        }
+        assert!(decrypt_private_key(&AES_KEY, &public_key, "not hex").is_err());
+    }
+
+    #[test]
+    fn aes_keys_must_be_256_bits() {
+        assert_eq!(parse_aes_key(&AES_KEY.to_hex::<String>()).unwrap(), AES_KEY);
+        assert!(parse_aes_key(&[7u8; 16].to_hex::<String>()).is_err());
+        assert!(parse_aes_key("not hex").is_err());
+    }
+}
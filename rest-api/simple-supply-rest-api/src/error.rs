@@ -0,0 +1,176 @@
+use std::fmt;
+
+use actix_web::error::{BlockingError, JsonPayloadError};
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::batch_validation::BatchValidationError;
+use crate::encryption::EncryptionError;
+use crate::messaging::MessagingError;
+
+/// Errors returned by the handlers, each rendered as a JSON body with a
+/// message and a stable code clients can match on
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Unauthorized(String),
+    NotFound(String),
+    Conflict(String),
+    /// The transaction processor rejected a transaction
+    InvalidTransaction(String),
+    PayloadTooLarge(String),
+    /// The client has to wait this many seconds before trying again
+    TooManyRequests(u64),
+    ValidatorUnavailable(String),
+    /// Anything else, which is logged but not shown to the client
+    InternalError(String),
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ErrorResponse {
+    error: String,
+    code: &'static str,
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::InvalidTransaction(_) => "invalid_transaction",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::TooManyRequests(_) => "too_many_requests",
+            ApiError::ValidatorUnavailable(_) => "validator_unavailable",
+            ApiError::InternalError(_) => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::InvalidTransaction(msg)
+            | ApiError::PayloadTooLarge(msg)
+            | ApiError::ValidatorUnavailable(msg)
+            | ApiError::InternalError(msg) => write!(f, "{}", msg),
+            ApiError::TooManyRequests(seconds) => {
+                write!(f, "Too many requests, retry in {} seconds", seconds)
+            }
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::InvalidTransaction(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::ValidatorUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let error = match self {
+            ApiError::InternalError(msg) => {
+                error!("Internal error: {}", msg);
+                "Internal server error".to_string()
+            }
+            _ => self.to_string(),
+        };
+        let mut response = HttpResponse::build(self.status_code());
+        if let ApiError::TooManyRequests(seconds) = self {
+            response.header(RETRY_AFTER, seconds.to_string());
+        }
+        response.json(ErrorResponse {
+            error,
+            code: self.code(),
+        })
+    }
+}
+
+/// Error handler for the JSON, query and path extractors, so that requests
+/// they cannot parse get the same error body as the rest of the API
+pub fn bad_request<E: fmt::Display>(err: E, _: &HttpRequest) -> actix_web::Error {
+    ApiError::BadRequest(err.to_string()).into()
+}
+
+/// Error handler for the JSON extractor, which also rejects bodies over the
+/// size limit
+pub fn json_error(err: JsonPayloadError, _: &HttpRequest) -> actix_web::Error {
+    match err {
+        JsonPayloadError::Overflow => ApiError::PayloadTooLarge(err.to_string()),
+        err => ApiError::BadRequest(err.to_string()),
+    }
+    .into()
+}
+
+impl From<MessagingError> for ApiError {
+    fn from(err: MessagingError) -> Self {
+        match err {
+            MessagingError::InvalidTransaction(msg) => ApiError::InvalidTransaction(msg),
+            err => ApiError::ValidatorUnavailable(err.to_string()),
+        }
+    }
+}
+
+impl From<BatchValidationError> for ApiError {
+    fn from(err: BatchValidationError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<diesel::result::Error> for ApiError {
+    fn from(err: diesel::result::Error) -> Self {
+        ApiError::InternalError(format!("database error: {}", err))
+    }
+}
+
+impl From<BlockingError<ApiError>> for ApiError {
+    fn from(err: BlockingError<ApiError>) -> Self {
+        match err {
+            BlockingError::Error(err) => err,
+            BlockingError::Canceled => {
+                ApiError::InternalError("blocking call was canceled".to_string())
+            }
+        }
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for ApiError {
+    fn from(err: diesel::r2d2::PoolError) -> Self {
+        ApiError::InternalError(format!("no database connection: {}", err))
+    }
+}
+
+impl From<EncryptionError> for ApiError {
+    fn from(err: EncryptionError) -> Self {
+        ApiError::InternalError(err.to_string())
+    }
+}
+
+impl From<bcrypt::BcryptError> for ApiError {
+    fn from(err: bcrypt::BcryptError) -> Self {
+        ApiError::InternalError(format!("password hashing failed: {}", err))
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for ApiError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        ApiError::InternalError(format!("token generation failed: {}", err))
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use actix_web::web::Bytes;
+use futures::channel::mpsc;
+use postgres::fallible_iterator::FallibleIterator;
+use postgres::{Client, NoTls};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Channel the `records_notify_change` trigger publishes on
+const RECORD_CHANGES_CHANNEL: &str = "record_changes";
+/// How often idle streams get a comment, which keeps proxies from closing
+/// them and lets us notice clients that went away
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// How long to wait before listening again after losing the database
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A new version of a record, as published by the database
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RecordEvent {
+    pub event: String,
+    pub record_id: String,
+    pub owner: Option<String>,
+    pub previous_owner: Option<String>,
+    pub latitude: Option<i64>,
+    pub longitude: Option<i64>,
+    pub in_violation: bool,
+    pub timestamp: Option<i64>,
+    pub block_num: i64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, JsonSchema)]
+pub struct EventFilter {
+    pub record_id: Option<String>,
+    pub owner: Option<String>,
+}
+
+impl EventFilter {
+    /// Transfers match both the new and the previous owner
+    fn matches(&self, event: &RecordEvent) -> bool {
+        let record_matches = self
+            .record_id
+            .iter()
+            .all(|record_id| record_id == &event.record_id);
+        let owner_matches = self.owner.iter().all(|owner| {
+            event.owner.as_ref() == Some(owner) || event.previous_owner.as_ref() == Some(owner)
+        });
+        record_matches && owner_matches
+    }
+}
+
+struct Subscriber {
+    filter: EventFilter,
+    sender: mpsc::UnboundedSender<Bytes>,
+}
+
+/// Fans record changes out to the open server-sent event streams. Changes
+/// are received with LISTEN on a dedicated thread.
+#[derive(Clone)]
+pub struct EventBroker {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBroker {
+    pub fn listen(database_url: String) -> EventBroker {
+        let broker = EventBroker {
+            subscribers: Arc::default(),
+        };
+
+        let listener = broker.clone();
+        thread::Builder::new()
+            .name("record-changes".to_string())
+            .spawn(move || loop {
+                if let Err(err) = listener.receive_changes(&database_url) {
+                    warn!("Stopped listening for record changes: {}", err);
+                }
+                thread::sleep(RECONNECT_DELAY);
+            })
+            .expect("Failed to start record change thread");
+
+        broker
+    }
+
+    /// Returns a stream of server-sent events for the changes matching
+    /// `filter`
+    pub fn subscribe(&self, filter: EventFilter) -> mpsc::UnboundedReceiver<Bytes> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Subscriber { filter, sender });
+        receiver
+    }
+
+    fn receive_changes(&self, database_url: &str) -> Result<(), postgres::Error> {
+        let mut client = Client::connect(database_url, NoTls)?;
+        client.batch_execute(&format!("LISTEN {}", RECORD_CHANGES_CHANNEL))?;
+        info!("Listening for record changes");
+
+        let mut notifications = client.notifications();
+        let mut iter = notifications.timeout_iter(KEEP_ALIVE_INTERVAL);
+        loop {
+            match iter.next()? {
+                Some(notification) => {
+                    match serde_json::from_str::<RecordEvent>(notification.payload()) {
+                        Ok(event) => self.publish(&event),
+                        Err(err) => warn!("Ignoring malformed record change: {}", err),
+                    }
+                }
+                None => self.broadcast(|_| true, Bytes::from_static(b": keep-alive\n\n")),
+            }
+        }
+    }
+
+    fn publish(&self, event: &RecordEvent) {
+        let data = serde_json::to_string(event).expect("record events always serialize");
+        let frame = Bytes::from(format!("event: {}\ndata: {}\n\n", event.event, data));
+        self.broadcast(|filter| filter.matches(event), frame);
+    }
+
+    /// Sends `frame` to the matching subscribers, dropping any whose stream
+    /// has been closed
+    fn broadcast<F: Fn(&EventFilter) -> bool>(&self, matches: F, frame: Bytes) {
+        self.subscribers.lock().unwrap().retain(|subscriber| {
+            if subscriber.sender.is_closed() {
+                return false;
+            }
+            !matches(&subscriber.filter) || subscriber.sender.unbounded_send(frame.clone()).is_ok()
+        });
+    }
+}
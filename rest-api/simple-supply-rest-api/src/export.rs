@@ -0,0 +1,105 @@
+use actix_web::web::Bytes;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::result::QueryResult;
+use futures::stream::{self, Stream};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::database;
+use crate::error::ApiError;
+
+/// Rows read from the database per query while exporting
+const EXPORT_CHUNK_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Streams every row returned by `fetch_chunk`, which is called with the id
+/// of the last row sent until it returns less than a full chunk. Only one
+/// chunk is held in memory at a time, and queries run on the blocking thread
+/// pool.
+pub fn export_stream<T, F, I>(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    format: ExportFormat,
+    fetch_chunk: F,
+    id: I,
+) -> impl Stream<Item = Result<Bytes, ApiError>>
+where
+    T: Serialize + Send + 'static,
+    F: Fn(&PgConnection, i64, i64) -> QueryResult<Vec<T>> + Clone + Send + 'static,
+    I: Fn(&T) -> i64 + Clone + 'static,
+{
+    // The state is the id to continue after, and whether this is the first
+    // chunk, which carries the CSV header
+    stream::unfold(Some((0, true)), move |state| {
+        let pool = pool.clone();
+        let fetch_chunk = fetch_chunk.clone();
+        let id = id.clone();
+        async move {
+            let (after_id, first) = state?;
+            let rows = database::with_connection(&pool, move |conn| {
+                Ok(fetch_chunk(conn, after_id, EXPORT_CHUNK_SIZE)?)
+            })
+            .await;
+
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(err) => return Some((Err(err), None)),
+            };
+            let next = match rows.last() {
+                Some(last) if rows.len() as i64 == EXPORT_CHUNK_SIZE => Some((id(last), false)),
+                _ => None,
+            };
+            if rows.is_empty() {
+                return None;
+            }
+            Some((encode(format, &rows, first), next))
+        }
+    })
+}
+
+fn encode<T: Serialize>(format: ExportFormat, rows: &[T], header: bool) -> Result<Bytes, ApiError> {
+    let encoding_failed = |err: &dyn std::fmt::Display| {
+        ApiError::InternalError(format!("cannot encode export: {}", err))
+    };
+    let mut buffer = Vec::new();
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(header)
+                .from_writer(&mut buffer);
+            for row in rows {
+                writer.serialize(row).map_err(|err| encoding_failed(&err))?;
+            }
+            writer.flush().map_err(|err| encoding_failed(&err))?;
+        }
+        ExportFormat::Ndjson => {
+            for row in rows {
+                serde_json::to_writer(&mut buffer, row).map_err(|err| encoding_failed(&err))?;
+                buffer.push(b'\n');
+            }
+        }
+    }
+    Ok(Bytes::from(buffer))
+}
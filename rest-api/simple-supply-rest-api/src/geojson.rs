@@ -0,0 +1,34 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+pub const CONTENT_TYPE: &str = "application/geo+json";
+
+/// Converts coordinates in millionths of a degree to a GeoJSON position,
+/// which is longitude first
+pub fn position(latitude: i64, longitude: i64) -> [f64; 2] {
+    [longitude as f64 / 1e6, latitude as f64 / 1e6]
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Geometry {
+    Point { coordinates: [f64; 2] },
+    LineString { coordinates: Vec<[f64; 2]> },
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "type", rename = "Feature")]
+pub struct Feature {
+    pub geometry: Option<Geometry>,
+    pub properties: Value,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "type", rename = "FeatureCollection")]
+pub struct FeatureCollection<M: Serialize> {
+    pub features: Vec<Feature>,
+    /// Foreign member for anything that isn't part of GeoJSON, such as paging
+    #[serde(flatten)]
+    pub metadata: M,
+}
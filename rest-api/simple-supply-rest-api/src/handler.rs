@@ -1,75 +1,1428 @@
+use crate::authentication::{generate_auth_token, AuthenticatedAgent};
+use crate::batch_validation::validate_batch_list;
 use crate::database;
+use crate::encryption::{decrypt_private_key, encrypt_private_key};
+use crate::error::ApiError;
+use crate::events::EventFilter;
+use crate::export::{export_stream, ExportFormat};
+use crate::geojson::{self, Feature, FeatureCollection, Geometry};
+use crate::idempotency::{Replay, ReplayRecord};
 use crate::model::*;
+use crate::openapi;
 use crate::Server;
 
-use actix_web::error::ErrorInternalServerError;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
 use actix_web::{web, Error, HttpResponse, Result};
-use bcrypt::{hash, DEFAULT_COST};
+use bcrypt::{hash, verify, DEFAULT_COST};
 use chrono::Utc;
-use openssl::aes::{aes_ige, AesKey};
-use openssl::symm::Mode;
-use rustc_hex::{FromHex, ToHex};
+use diesel::pg::PgConnection;
+use diesel::result::QueryResult;
+use diesel::RunQueryDsl;
+use futures::StreamExt;
+use protos::payload;
+use sawtooth_sdk::messages::batch::BatchList;
+use sawtooth_sdk::messages::client_batch_submit::ClientBatchStatus_Status;
+use sawtooth_sdk::signing::secp256k1::Secp256k1PrivateKey;
+use sawtooth_sdk::signing::PrivateKey;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateAgentRequest {
     name: String,
     password: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateAgentResponse {
     authorization: String,
 }
 
+/// What is kept of a created agent for retries with the same idempotency
+/// key, which get a new token rather than a stored one
+#[derive(Debug, Serialize, Deserialize)]
+struct CreatedAgent {
+    public_key: String,
+    batch_id: String,
+}
+
 pub async fn create_agent(
     server: web::Data<Server>,
     req: web::Json<CreateAgentRequest>,
-) -> Result<HttpResponse, Error> {
-    println!("{:?}", req);
+    replay: Option<Replay>,
+) -> Result<HttpResponse, ApiError> {
+    if let Some(Replay(record)) = replay {
+        let created: CreatedAgent = serde_json::from_value(record)
+            .map_err(|err| ApiError::InternalError(err.to_string()))?;
+        // The password is checked again, as it may have been changed since
+        let password = req.into_inner().password;
+        let auth = database::with_connection(&server.pool, move |conn| {
+            check_password(conn, &created.public_key, &password)
+        })
+        .await?;
+        let token = generate_auth_token(&server.jwt_secret, &auth.public_key, auth.token_version)?;
+        return Ok(HttpResponse::Created().json(CreateAgentResponse {
+            authorization: token,
+        }));
+    }
+
     let (private_key, public_key) = server.messenger.get_new_key_pair();
-    server
+    let batch_id = server
         .messenger
         .send_create_agent_transaction(&*private_key, &req.name, Utc::now().timestamp())
-        .map_err(ErrorInternalServerError)?;
+        .await?;
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let encrypted_private_key = encrypt_private_key(
+        &server.aes_key,
+        &public_key.as_hex(),
+        private_key.as_slice(),
+    )?;
+
+    let password = req.password.clone();
+    let auth_public_key = public_key.as_hex();
+    database::with_connection(&server.pool, move |conn| {
+        let auth = NewAuth {
+            public_key: auth_public_key,
+            hashed_password: hash_password(&password)?,
+            encrypted_private_key,
+        };
+        database::insert_auth(conn, &auth)?;
+        Ok(())
+    })
+    .await?;
+
+    let token = generate_auth_token(&server.jwt_secret, &public_key.as_hex(), 0)?;
+
+    let mut response = HttpResponse::Created().json(CreateAgentResponse {
+        authorization: token,
+    });
+    response
+        .extensions_mut()
+        .insert(ReplayRecord(json!(CreatedAgent {
+            public_key: public_key.as_hex(),
+            batch_id,
+        })));
+    Ok(response)
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AgentResponse {
+    public_key: String,
+    name: String,
+    timestamp: i64,
+}
+
+impl From<Agent> for AgentResponse {
+    fn from(agent: Agent) -> Self {
+        AgentResponse {
+            public_key: agent.public_key,
+            name: agent.name,
+            timestamp: agent.timestamp,
+        }
+    }
+}
 
-    let aes_key: Vec<u8> = FromHex::from_hex("ffffffffffffffffffffffffffffffff").unwrap();
-    let encrypted_private_key =
-        encrypt_private_key(&aes_key, public_key.as_slice(), private_key.as_slice())
-            .map_err(ErrorInternalServerError)?;
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StateQuery {
+    /// Read the state as of this block number
+    at_block: Option<i64>,
+    /// Read the state as of the last block committed at or before this Unix
+    /// UTC timestamp. Blocks are dated by the latest timestamp of the
+    /// transactions in them, which are set by whoever signed them. Those
+    /// dated more than a few minutes into the future are left out.
+    at_time: Option<i64>,
+}
+
+/// The block to read state at, or `None` for the current state
+fn resolve_block(conn: &PgConnection, query: &StateQuery) -> Result<Option<i64>, ApiError> {
+    match (query.at_block, query.at_time) {
+        (Some(_), Some(_)) => Err(ApiError::BadRequest(
+            "at_block and at_time cannot be used together".to_string(),
+        )),
+        (Some(block_num), None) => Ok(Some(block_num)),
+        (None, Some(timestamp)) => database::fetch_block_num_at(conn, timestamp)?
+            .map(Some)
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("No block was committed at or before {}", timestamp))
+            }),
+        (None, None) => Ok(None),
+    }
+}
 
-    let hashed_password = hash_password(req.password.clone());
+const DEFAULT_PAGE_LIMIT: i64 = 100;
+const MAX_PAGE_LIMIT: i64 = 1000;
 
-    let auth = NewAuth {
-        public_key: public_key.as_hex(),
-        hashed_password: hashed_password,
-        encrypted_private_key: encrypted_private_key,
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PagingQuery {
+    /// Where the page starts: the key of its first item, or when sorting by
+    /// another field the `next_position` of the page before
+    start: Option<String>,
+    limit: Option<i64>,
+    /// Field to sort by, prefixed with `-` for descending order. Agents sort
+    /// by `public_key`, `name` or `last_updated`, records by `record_id` or
+    /// `last_updated`. Items with the same value are ordered by their key.
+    sort: Option<String>,
+}
+
+/// Fields besides its key which a list can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortField {
+    Name,
+    LastUpdated,
+}
+
+impl SortField {
+    fn name(self) -> &'static str {
+        match self {
+            SortField::Name => "name",
+            SortField::LastUpdated => "last_updated",
+        }
+    }
+}
+
+impl PagingQuery {
+    fn to_paging(&self, key: &str, fields: &[SortField]) -> Result<database::Paging, ApiError> {
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+            return Err(ApiError::BadRequest(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_LIMIT
+            )));
+        }
+        let (name, descending) = match self.sort.as_deref() {
+            None => (key, false),
+            Some(sort) if sort.starts_with('-') => (&sort[1..], true),
+            Some(sort) => (sort, false),
+        };
+        let field = fields.iter().find(|field| field.name() == name);
+        let (start, sort) = match field {
+            None if name == key => (self.start.clone(), None),
+            None => {
+                let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+                return Err(ApiError::BadRequest(format!(
+                    "Cannot sort by {}, only by {} or {}, optionally prefixed with -",
+                    name,
+                    key,
+                    names.join(", ")
+                )));
+            }
+            Some(SortField::Name) => {
+                let (value, start) = self.split_start()?;
+                (start, Some(database::Sort::Name(value)))
+            }
+            Some(SortField::LastUpdated) => {
+                let (value, start) = self.split_start()?;
+                (start, Some(database::Sort::LastUpdated(value)))
+            }
+        };
+        Ok(database::Paging {
+            start,
+            limit,
+            descending,
+            sort,
+        })
+    }
+
+    /// Splits a `start` of `[value, key]`, which is how a position is given
+    /// when sorting by a field other than the key
+    fn split_start<T: DeserializeOwned>(&self) -> Result<(Option<T>, Option<String>), ApiError> {
+        match &self.start {
+            None => Ok((None, None)),
+            Some(start) => serde_json::from_str::<(T, String)>(start)
+                .map(|(value, key)| (Some(value), Some(key)))
+                .map_err(|_| {
+                    ApiError::BadRequest(format!(
+                        "start must be a next_position returned with this sort, got {}",
+                        start
+                    ))
+                }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PagingResponse {
+    start: Option<String>,
+    limit: i64,
+    /// Pass as `start` to load the next page
+    next_position: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListResponse<T> {
+    data: Vec<T>,
+    paging: PagingResponse,
+}
+
+/// Splits off the extra row loaded past the end of the page, whose key is
+/// where the next page starts
+fn paginate<T>(
+    mut rows: Vec<T>,
+    paging: &database::Paging,
+    key: impl Fn(&T) -> String,
+) -> (Vec<T>, PagingResponse) {
+    let next_position = if rows.len() as i64 > paging.limit {
+        rows.pop().map(|row| key(&row))
+    } else {
+        None
+    };
+    let paging = PagingResponse {
+        start: paging.start.clone(),
+        limit: paging.limit,
+        next_position,
     };
-    let conn = server.pool.get().map_err(ErrorInternalServerError)?;
-    database::insert_auth(&conn, &auth).map_err(ErrorInternalServerError)?;
+    (rows, paging)
+}
 
-    let token = generate_auth_token("secret_key".to_string(), public_key.as_hex());
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AgentFilterQuery {
+    name: Option<String>,
+    updated_since: Option<i64>,
+}
+
+pub async fn list_agents(
+    server: web::Data<Server>,
+    query: web::Query<StateQuery>,
+    filter: web::Query<AgentFilterQuery>,
+    paging: web::Query<PagingQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let paging = paging.to_paging("public_key", &[SortField::Name, SortField::LastUpdated])?;
+    let filter = database::AgentFilter {
+        name: filter.name.clone(),
+        updated_since: filter.updated_since,
+    };
+
+    let query = query.into_inner();
+    let (agents, paging) = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        let agents = database::fetch_agents(conn, at_block, &filter, &paging)?;
+        Ok(paginate(agents, &paging, |agent| match paging.sort {
+            None => agent.public_key.clone(),
+            Some(database::Sort::Name(_)) => json!([agent.name, agent.public_key]).to_string(),
+            Some(database::Sort::LastUpdated(_)) => {
+                json!([agent.timestamp, agent.public_key]).to_string()
+            }
+        }))
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ListResponse {
+        data: agents
+            .into_iter()
+            .map(AgentResponse::from)
+            .collect::<Vec<_>>(),
+        paging,
+    }))
+}
 
-    Ok(HttpResponse::Created().json(CreateAgentResponse {
+pub async fn fetch_agent(
+    server: web::Data<Server>,
+    public_key: web::Path<String>,
+    query: web::Query<StateQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (public_key, query) = (public_key.into_inner(), query.into_inner());
+    let agent = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        database::fetch_agent(conn, &public_key, at_block)?
+            .ok_or_else(|| ApiError::NotFound(format!("Agent {} not found", public_key)))
+    })
+    .await?;
+    Ok(HttpResponse::Ok().json(AgentResponse::from(agent)))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AuthenticationRequest {
+    public_key: String,
+    password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AuthenticationResponse {
+    authorization: String,
+}
+
+pub async fn authenticate(
+    server: web::Data<Server>,
+    req: web::Json<AuthenticationRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let req = req.into_inner();
+    let auth = database::with_connection(&server.pool, move |conn| {
+        check_password(conn, &req.public_key, &req.password)
+    })
+    .await?;
+
+    let token = generate_auth_token(&server.jwt_secret, &auth.public_key, auth.token_version)?;
+    Ok(HttpResponse::Ok().json(AuthenticationResponse {
         authorization: token,
     }))
 }
 
-fn encrypt_private_key(aes_key: &[u8], public_key: &[u8], private_key: &[u8]) -> Result<String> {
-    let key = AesKey::new_encrypt(aes_key).expect("invalid aes_key");
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ChangePasswordRequest {
+    current_password: String,
+    new_password: String,
+}
+
+/// Changes the password of the authenticated agent. The private key is
+/// encrypted again on the way, which moves keys stored under an older
+/// scheme onto the current one. Every token issued so far stops working, so
+/// a new one is returned.
+///
+/// There is no way to reset a forgotten password: the server knows nothing
+/// else about an agent to check who is asking.
+pub async fn change_password(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    req: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if req.new_password.is_empty() {
+        return Err(ApiError::BadRequest(
+            "new_password cannot be empty".to_string(),
+        ));
+    }
 
-    let input = private_key;
-    let mut output = [0u8; 32];
-    let mut iv = public_key.to_vec();
+    let req = req.into_inner();
+    let aes_key = server.aes_key.clone();
+    let auth = database::with_connection(&server.pool, move |conn| {
+        let auth = check_password(conn, &agent.public_key, &req.current_password)?;
+        let encrypted_private_key = match &auth.encrypted_private_key {
+            Some(encrypted_private_key) => {
+                let private_key =
+                    decrypt_private_key(&aes_key, &auth.public_key, encrypted_private_key)?;
+                Some(encrypt_private_key(
+                    &aes_key,
+                    &auth.public_key,
+                    private_key.as_slice(),
+                )?)
+            }
+            None => None,
+        };
 
-    aes_ige(&input, &mut output, &key, &mut iv[..32], Mode::Encrypt);
-    Ok(output.to_hex())
+        let auth = Auth {
+            hashed_password: hash_password(&req.new_password)?,
+            encrypted_private_key,
+            token_version: auth.token_version + 1,
+            public_key: auth.public_key,
+        };
+        database::update_auth(conn, &auth)?;
+        Ok(auth)
+    })
+    .await?;
+
+    let token = generate_auth_token(&server.jwt_secret, &auth.public_key, auth.token_version)?;
+    Ok(HttpResponse::Ok().json(AuthenticationResponse {
+        authorization: token,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportKeyRequest {
+    password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportKeyResponse {
+    public_key: String,
+    /// Hex encoded secp256k1 private key
+    private_key: String,
+}
+
+/// Hands the authenticated agent its private key so it can sign its own
+/// transactions. The password is asked for again, since a token alone
+/// should not be enough to take the key.
+pub async fn export_private_key(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    req: web::Json<ExportKeyRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let password = req.into_inner().password;
+    let auth = database::with_connection(&server.pool, move |conn| {
+        check_password(conn, &agent.public_key, &password)
+    })
+    .await?;
+    let private_key = decrypt_private_key(
+        &server.aes_key,
+        &auth.public_key,
+        stored_private_key(&auth)?,
+    )?;
+
+    info!("Exported the private key of agent {}", auth.public_key);
+    Ok(HttpResponse::Ok()
+        .header("Cache-Control", "no-store")
+        .json(ExportKeyResponse {
+            public_key: auth.public_key,
+            private_key: private_key.as_hex(),
+        }))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteKeyRequest {
+    password: String,
+}
+
+/// Removes the server's copy of the authenticated agent's private key, once
+/// it has been exported. The agent can still log in, but has to sign its
+/// own transactions and submit them to `/batches` from then on.
+pub async fn delete_private_key(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    req: web::Json<DeleteKeyRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let password = req.into_inner().password;
+    let public_key = agent.public_key.clone();
+    database::with_connection(&server.pool, move |conn| {
+        let auth = check_password(conn, &public_key, &password)?;
+        stored_private_key(&auth)?;
+
+        database::update_auth(
+            conn,
+            &Auth {
+                encrypted_private_key: None,
+                ..auth
+            },
+        )?;
+        Ok(())
+    })
+    .await?;
+
+    info!(
+        "Deleted the stored private key of agent {}",
+        agent.public_key
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PropertyBound {
+    name: String,
+    min: i64,
+    max: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PropertyValue {
+    name: String,
+    value: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CreateRecordRequest {
+    record_id: String,
+    latitude: i64,
+    longitude: i64,
+    #[serde(default)]
+    bounds: Vec<PropertyBound>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateRecordRequest {
+    latitude: i64,
+    longitude: i64,
+    #[serde(default)]
+    properties: Vec<PropertyValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TransferRecordRequest {
+    receiving_agent: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ComponentsRequest {
+    component_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct OwnerResponse {
+    agent_id: String,
+    timestamp: i64,
 }
 
-fn hash_password(password: String) -> String {
-    hash(password, DEFAULT_COST).unwrap()
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LocationResponse {
+    latitude: i64,
+    longitude: i64,
+    timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PropertyReportResponse {
+    name: String,
+    value: i64,
+    timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RecordResponse {
+    record_id: String,
+    owners: Vec<OwnerResponse>,
+    locations: Vec<LocationResponse>,
+    components: Vec<String>,
+    assembly: Option<String>,
+    recalled: bool,
+    // Property bounds and reports are only kept in validator state, so they
+    // are left out of responses built from the reporting database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bounds: Option<Vec<PropertyBound>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reports: Option<Vec<PropertyReportResponse>>,
+    in_violation: bool,
+}
+
+impl From<&protos::record::Record> for RecordResponse {
+    fn from(record: &protos::record::Record) -> Self {
+        RecordResponse {
+            record_id: record.get_record_id().to_string(),
+            owners: record
+                .get_owners()
+                .iter()
+                .map(|owner| OwnerResponse {
+                    agent_id: owner.get_agent_id().to_string(),
+                    timestamp: owner.get_timestamp() as i64,
+                })
+                .collect(),
+            locations: record
+                .get_locations()
+                .iter()
+                .map(|location| LocationResponse {
+                    latitude: location.get_latitude(),
+                    longitude: location.get_longitude(),
+                    timestamp: location.get_timestamp() as i64,
+                })
+                .collect(),
+            components: record.get_components().to_vec(),
+            assembly: match record.get_assembly() {
+                "" => None,
+                assembly => Some(assembly.to_string()),
+            },
+            recalled: record.get_recalled(),
+            bounds: Some(
+                record
+                    .get_bounds()
+                    .iter()
+                    .map(|bound| PropertyBound {
+                        name: bound.get_name().to_string(),
+                        min: bound.get_min(),
+                        max: bound.get_max(),
+                    })
+                    .collect(),
+            ),
+            reports: Some(
+                record
+                    .get_reports()
+                    .iter()
+                    .map(|report| PropertyReportResponse {
+                        name: report.get_name().to_string(),
+                        value: report.get_value(),
+                        timestamp: report.get_timestamp() as i64,
+                    })
+                    .collect(),
+            ),
+            in_violation: record.get_in_violation(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RecordFilterQuery {
+    owner: Option<String>,
+    min_latitude: Option<i64>,
+    min_longitude: Option<i64>,
+    max_latitude: Option<i64>,
+    max_longitude: Option<i64>,
+    updated_since: Option<i64>,
+}
+
+impl RecordFilterQuery {
+    fn to_filter(&self) -> Result<database::RecordFilter, ApiError> {
+        let bounding_box = match (
+            self.min_latitude,
+            self.min_longitude,
+            self.max_latitude,
+            self.max_longitude,
+        ) {
+            (Some(min_latitude), Some(min_longitude), Some(max_latitude), Some(max_longitude)) => {
+                Some(database::BoundingBox {
+                    min_latitude,
+                    min_longitude,
+                    max_latitude,
+                    max_longitude,
+                })
+            }
+            (None, None, None, None) => None,
+            _ => return Err(ApiError::BadRequest(
+                "A bounding box needs min_latitude, min_longitude, max_latitude and max_longitude"
+                    .to_string(),
+            )),
+        };
+        Ok(database::RecordFilter {
+            owner: self.owner.clone(),
+            bounding_box,
+            updated_since: self.updated_since,
+        })
+    }
+}
+
+/// Where a page starting at `record` starts, in the form `start` takes
+fn record_position(paging: &database::Paging, record: &Record) -> String {
+    match paging.sort {
+        None => record.record_id.clone(),
+        // Sorted like the database, which puts records without any history
+        // first
+        Some(_) => json!([record.last_updated.unwrap_or(0), record.record_id]).to_string(),
+    }
+}
+
+pub async fn list_records(
+    server: web::Data<Server>,
+    query: web::Query<StateQuery>,
+    filter: web::Query<RecordFilterQuery>,
+    paging: web::Query<PagingQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let paging = paging.to_paging("record_id", &[SortField::LastUpdated])?;
+    let filter = filter.to_filter()?;
+
+    let query = query.into_inner();
+    let (records, paging) = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        let records = database::fetch_records(conn, at_block, &filter, &paging)?;
+        let (records, paging) =
+            paginate(records, &paging, |record| record_position(&paging, record));
+        Ok((build_record_responses(conn, records, at_block)?, paging))
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ListResponse {
+        data: records,
+        paging,
+    }))
+}
+
+pub async fn fetch_record(
+    server: web::Data<Server>,
+    record_id: web::Path<String>,
+    query: web::Query<StateQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (record_id, query) = (record_id.into_inner(), query.into_inner());
+    let mut records = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        let record = database::fetch_record(conn, &record_id, at_block)?
+            .ok_or_else(|| ApiError::NotFound(format!("Record {} not found", record_id)))?;
+        Ok(build_record_responses(conn, vec![record], at_block)?)
+    })
+    .await?;
+    Ok(HttpResponse::Ok().json(records.remove(0)))
 }
 
-fn generate_auth_token(_secret_key: String, public_key: String) -> String {
-    public_key
+pub async fn fetch_record_track(
+    server: web::Data<Server>,
+    record_id: web::Path<String>,
+    query: web::Query<StateQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (record_id, query) = (record_id.into_inner(), query.into_inner());
+    let (record, locations) = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        let record = database::fetch_record(conn, &record_id, at_block)?
+            .ok_or_else(|| ApiError::NotFound(format!("Record {} not found", record_id)))?;
+        let locations = database::fetch_record_locations(
+            conn,
+            std::slice::from_ref(&record.record_id),
+            at_block,
+        )?;
+        Ok((record, locations))
+    })
+    .await?;
+
+    let mut coordinates: Vec<[f64; 2]> = locations
+        .iter()
+        .map(|location| geojson::position(location.latitude, location.longitude))
+        .collect();
+    let timestamps: Vec<i64> = locations
+        .iter()
+        .map(|location| location.timestamp)
+        .collect();
+    // A LineString needs at least two positions
+    let geometry = match coordinates.len() {
+        0 => None,
+        1 => coordinates
+            .pop()
+            .map(|coordinates| Geometry::Point { coordinates }),
+        _ => Some(Geometry::LineString { coordinates }),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(geojson::CONTENT_TYPE)
+        .json(Feature {
+            geometry,
+            properties: json!({
+                "record_id": record.record_id,
+                "timestamps": timestamps,
+            }),
+        }))
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PositionsMetadata {
+    paging: PagingResponse,
+}
+
+/// Current positions of records as points, filtered like `list_records`
+pub async fn list_record_positions(
+    server: web::Data<Server>,
+    query: web::Query<StateQuery>,
+    filter: web::Query<RecordFilterQuery>,
+    paging: web::Query<PagingQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let paging = paging.to_paging("record_id", &[SortField::LastUpdated])?;
+    let filter = filter.to_filter()?;
+
+    let query = query.into_inner();
+    let (records, paging) = database::with_connection(&server.pool, move |conn| {
+        let at_block = resolve_block(conn, &query)?;
+        let records = database::fetch_records(conn, at_block, &filter, &paging)?;
+        Ok(paginate(records, &paging, |record| {
+            record_position(&paging, record)
+        }))
+    })
+    .await?;
+
+    let features = records
+        .into_iter()
+        .filter_map(|record| {
+            let coordinates = geojson::position(record.latitude?, record.longitude?);
+            Some(Feature {
+                geometry: Some(Geometry::Point { coordinates }),
+                properties: json!({
+                    "record_id": record.record_id,
+                    "owner": record.owner,
+                    "last_updated": record.last_updated,
+                    "in_violation": record.in_violation,
+                }),
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .content_type(geojson::CONTENT_TYPE)
+        .json(FeatureCollection {
+            features,
+            metadata: PositionsMetadata { paging },
+        }))
+}
+
+/// Pushes record changes as server-sent events, optionally only those for
+/// one record or owner
+pub async fn record_events(
+    server: web::Data<Server>,
+    query: web::Query<EventFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let events = server.events.subscribe(query.into_inner());
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .streaming(events.map(Ok::<_, Error>)))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportQuery {
+    format: Option<ExportFormat>,
+    since: Option<i64>,
+    until: Option<i64>,
+    owner: Option<String>,
+}
+
+/// Streams the current rows of one table as CSV or NDJSON
+pub async fn export_table(
+    server: web::Data<Server>,
+    table: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let format = query.format.unwrap_or(ExportFormat::Csv);
+    let filter = database::ExportFilter {
+        since: query.since,
+        until: query.until,
+        owner: query.owner.clone(),
+    };
+    let pool = server.pool.clone();
+
+    let mut response = HttpResponse::Ok();
+    response.content_type(format.content_type()).header(
+        "Content-Disposition",
+        format!("attachment; filename=\"{}.{}\"", table, format.extension()),
+    );
+    let response = match table.as_str() {
+        "agents" => response.streaming(Box::pin(export_stream(
+            pool,
+            format,
+            move |conn, after_id, limit| database::export_agents(conn, &filter, after_id, limit),
+            |agent: &Agent| agent.id,
+        ))),
+        "records" => response.streaming(Box::pin(export_stream(
+            pool,
+            format,
+            move |conn, after_id, limit| database::export_records(conn, &filter, after_id, limit),
+            |record: &Record| record.id,
+        ))),
+        "owners" => response.streaming(Box::pin(export_stream(
+            pool,
+            format,
+            move |conn, after_id, limit| {
+                database::export_record_owners(conn, &filter, after_id, limit)
+            },
+            |owner: &RecordOwner| owner.id,
+        ))),
+        "locations" => response.streaming(Box::pin(export_stream(
+            pool,
+            format,
+            move |conn, after_id, limit| {
+                database::export_record_locations(conn, &filter, after_id, limit)
+            },
+            |location: &RecordLocation| location.id,
+        ))),
+        _ => {
+            return Err(ApiError::NotFound(format!(
+                "Cannot export {}, only agents, records, owners or locations",
+                table
+            )))
+        }
+    };
+    Ok(response)
+}
+
+/// Joins record rows with their owner and location histories, components
+/// and recall status as of the same block, loading each in one query for all
+/// records
+fn build_record_responses(
+    conn: &PgConnection,
+    records: Vec<Record>,
+    at_block: Option<i64>,
+) -> QueryResult<Vec<RecordResponse>> {
+    let record_ids: Vec<String> = records
+        .iter()
+        .map(|record| record.record_id.clone())
+        .collect();
+
+    let mut owners: HashMap<String, Vec<OwnerResponse>> = HashMap::new();
+    for owner in database::fetch_record_owners(conn, &record_ids, at_block)? {
+        owners
+            .entry(owner.record_id)
+            .or_default()
+            .push(OwnerResponse {
+                agent_id: owner.agent_id,
+                timestamp: owner.timestamp,
+            });
+    }
+    let mut locations: HashMap<String, Vec<LocationResponse>> = HashMap::new();
+    for location in database::fetch_record_locations(conn, &record_ids, at_block)? {
+        locations
+            .entry(location.record_id)
+            .or_default()
+            .push(LocationResponse {
+                latitude: location.latitude,
+                longitude: location.longitude,
+                timestamp: location.timestamp,
+            });
+    }
+    let mut components: HashMap<String, Vec<String>> = HashMap::new();
+    for (assembly, component) in database::fetch_record_components(conn, &record_ids, at_block)? {
+        components.entry(assembly).or_default().push(component);
+    }
+    let recalled: HashSet<String> = database::fetch_recalled(conn, &record_ids, at_block)?
+        .into_iter()
+        .collect();
+
+    Ok(records
+        .into_iter()
+        .map(|record| RecordResponse {
+            owners: owners.remove(&record.record_id).unwrap_or_default(),
+            locations: locations.remove(&record.record_id).unwrap_or_default(),
+            components: components.remove(&record.record_id).unwrap_or_default(),
+            recalled: recalled.contains(&record.record_id),
+            record_id: record.record_id,
+            assembly: record.assembly.filter(|assembly| !assembly.is_empty()),
+            bounds: None,
+            reports: None,
+            in_violation: record.in_violation,
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SubmitOptions {
+    /// Whether to wait for the batch to commit before responding
+    #[serde(default = "default_wait")]
+    wait: bool,
+}
+
+fn default_wait() -> bool {
+    true
+}
+
+pub async fn create_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    req: web::Json<CreateRecordRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if server
+        .messenger
+        .fetch_record(&req.record_id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(format!(
+            "Record {} already exists",
+            req.record_id
+        )));
+    }
+    let private_key = load_private_key(&server, &agent).await?;
+    let bounds = req
+        .bounds
+        .iter()
+        .map(|bound| payload::PropertyBound {
+            name: bound.name.clone(),
+            min: bound.min,
+            max: bound.max,
+            ..Default::default()
+        })
+        .collect();
+    let batch_id = server
+        .messenger
+        .send_create_record_transaction(
+            &private_key,
+            &req.record_id,
+            req.latitude,
+            req.longitude,
+            bounds,
+            Utc::now().timestamp(),
+        )
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &req.record_id).await?;
+    Ok(HttpResponse::Created().json(record))
+}
+
+pub async fn update_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    record_id: web::Path<String>,
+    req: web::Json<UpdateRecordRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let private_key = load_private_key(&server, &agent).await?;
+    let properties = req
+        .properties
+        .iter()
+        .map(|property| payload::PropertyValue {
+            name: property.name.clone(),
+            value: property.value,
+            ..Default::default()
+        })
+        .collect();
+    let batch_id = server
+        .messenger
+        .send_update_record_transaction(
+            &private_key,
+            &record_id,
+            req.latitude,
+            req.longitude,
+            properties,
+            Utc::now().timestamp(),
+        )
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &record_id).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+pub async fn transfer_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    record_id: web::Path<String>,
+    req: web::Json<TransferRecordRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let private_key = load_private_key(&server, &agent).await?;
+    let batch_id = server
+        .messenger
+        .send_transfer_record_transaction(
+            &private_key,
+            &record_id,
+            &req.receiving_agent,
+            Utc::now().timestamp(),
+        )
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &record_id).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+pub async fn assemble_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    record_id: web::Path<String>,
+    req: web::Json<ComponentsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let private_key = load_private_key(&server, &agent).await?;
+    let batch_id = server
+        .messenger
+        .send_assemble_record_transaction(
+            &private_key,
+            &record_id,
+            &req.component_ids,
+            Utc::now().timestamp(),
+        )
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &record_id).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+pub async fn disassemble_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    record_id: web::Path<String>,
+    req: web::Json<ComponentsRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let private_key = load_private_key(&server, &agent).await?;
+    let batch_id = server
+        .messenger
+        .send_disassemble_record_transaction(
+            &private_key,
+            &record_id,
+            &req.component_ids,
+            Utc::now().timestamp(),
+        )
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &record_id).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+pub async fn recall_record(
+    server: web::Data<Server>,
+    agent: AuthenticatedAgent,
+    options: web::Query<SubmitOptions>,
+    record_id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let private_key = load_private_key(&server, &agent).await?;
+    let batch_id = server
+        .messenger
+        .send_recall_record_transaction(&private_key, &record_id, Utc::now().timestamp())
+        .await?;
+    if !options.wait {
+        return Ok(submitted(batch_id));
+    }
+    server.messenger.wait_for_commit(&batch_id).await?;
+
+    let record = fetch_record_state(&server, &record_id).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SubmitBatchesResponse {
+    batch_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BatchStatusQuery {
+    /// Comma separated batch ids
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BatchStatusResponse {
+    id: String,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+pub async fn fetch_batch_statuses(
+    server: web::Data<Server>,
+    query: web::Query<BatchStatusQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let batch_ids: Vec<String> = query
+        .id
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect();
+    if batch_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one batch id is required".to_string(),
+        ));
+    }
+
+    let statuses = server
+        .messenger
+        .fetch_batch_statuses(batch_ids, false)
+        .await?;
+    let response: Vec<BatchStatusResponse> = statuses
+        .iter()
+        .map(|status| BatchStatusResponse {
+            id: status.get_batch_id().to_string(),
+            status: match status.get_status() {
+                ClientBatchStatus_Status::PENDING => "PENDING",
+                ClientBatchStatus_Status::COMMITTED => "COMMITTED",
+                ClientBatchStatus_Status::INVALID => "INVALID",
+                _ => "UNKNOWN",
+            }
+            .to_string(),
+            message: status
+                .get_invalid_transactions()
+                .first()
+                .map(|transaction| transaction.get_message().to_string()),
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Forwards batches signed by the client, whose keys the server never sees
+pub async fn submit_batches(
+    server: web::Data<Server>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, ApiError> {
+    // Read here rather than by the bytes extractor, whose size limit answers
+    // in plain text
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|err| ApiError::BadRequest(err.to_string()))?;
+        if body.len() + chunk.len() > server.max_batch_size {
+            return Err(ApiError::PayloadTooLarge(format!(
+                "Request body is larger than {} bytes",
+                server.max_batch_size
+            )));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let batch_list: BatchList = protobuf::parse_from_bytes(&body)
+        .map_err(|err| ApiError::BadRequest(format!("Malformed batch list: {}", err)))?;
+    validate_batch_list(&batch_list)?;
+
+    let batch_ids = batch_list
+        .get_batches()
+        .iter()
+        .map(|batch| batch.get_header_signature().to_string())
+        .collect();
+    server
+        .messenger
+        .submit_batches(batch_list.batches.into_vec())
+        .await?;
+
+    Ok(HttpResponse::Accepted().json(SubmitBatchesResponse { batch_ids }))
+}
+
+fn submitted(batch_id: String) -> HttpResponse {
+    HttpResponse::Accepted().json(SubmitBatchesResponse {
+        batch_ids: vec![batch_id],
+    })
+}
+
+/// Decrypts the signing key the server holds for the authenticated agent
+async fn load_private_key(
+    server: &Server,
+    agent: &AuthenticatedAgent,
+) -> Result<Secp256k1PrivateKey, ApiError> {
+    let public_key = agent.public_key.clone();
+    let auth = database::with_connection(&server.pool, move |conn| {
+        database::fetch_auth(conn, &public_key)?.ok_or_else(|| {
+            ApiError::Unauthorized("No signing key stored for this agent".to_string())
+        })
+    })
+    .await?;
+    decrypt_private_key(
+        &server.aes_key,
+        &auth.public_key,
+        stored_private_key(&auth)?,
+    )
+    .map_err(ApiError::from)
+}
+
+/// The encrypted private key of an agent, unless it has been deleted from
+/// the server after being exported
+fn stored_private_key(auth: &Auth) -> Result<&str, ApiError> {
+    auth.encrypted_private_key.as_deref().ok_or_else(|| {
+        ApiError::Conflict(
+            "The private key of this agent is no longer stored on the server; sign \
+             transactions with it and submit them to /batches"
+                .to_string(),
+        )
+    })
+}
+
+async fn fetch_record_state(server: &Server, record_id: &str) -> Result<RecordResponse, ApiError> {
+    let record = server
+        .messenger
+        .fetch_record(record_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Record {} not found", record_id)))?;
+    Ok(RecordResponse::from(&record))
+}
+
+/// How long the readiness check waits for a database connection
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReadinessResponse {
+    ready: bool,
+    /// `ok`, or why the reporting database can't be used
+    database: String,
+    /// `ok`, or why the validator can't be reached
+    validator: String,
+}
+
+/// Liveness check, which only shows that the server is answering
+pub async fn check_health() -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(json!({ "status": "ok" })))
+}
+
+/// Readiness check, which fails while the reporting database or the
+/// validator can't be reached
+pub async fn check_readiness(server: web::Data<Server>) -> Result<HttpResponse, ApiError> {
+    let pool = server.pool.clone();
+    let database = web::block(move || {
+        let conn = pool
+            .get_timeout(READY_TIMEOUT)
+            .map_err(|err| err.to_string())?;
+        diesel::sql_query("SELECT 1")
+            .execute(&conn)
+            .map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string());
+    let validator = server.messenger.ping().await;
+
+    let response = ReadinessResponse {
+        ready: database.is_ok() && validator.is_ok(),
+        database: database.map_or_else(|err| err, |_| "ok".to_string()),
+        validator: validator.map_or_else(|err| err.to_string(), |_| "ok".to_string()),
+    };
+    if response.ready {
+        Ok(HttpResponse::Ok().json(response))
+    } else {
+        Ok(HttpResponse::ServiceUnavailable().json(response))
+    }
+}
+
+/// Request, batch and connection pool metrics in the Prometheus text format
+pub async fn render_metrics(server: web::Data<Server>) -> Result<HttpResponse, ApiError> {
+    let (content_type, body) = server.metrics.render(&server.pool);
+    Ok(HttpResponse::Ok().content_type(content_type).body(body))
+}
+
+pub async fn fetch_openapi() -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(openapi::document()))
+}
+
+/// Fallback for requests that match no route
+pub async fn not_found() -> Result<HttpResponse, ApiError> {
+    Err(ApiError::NotFound("No such resource".to_string()))
+}
+
+/// Loads the credentials of an agent if `password` is theirs
+fn check_password(conn: &PgConnection, public_key: &str, password: &str) -> Result<Auth, ApiError> {
+    let auth = database::fetch_auth(conn, public_key)?
+        .ok_or_else(|| ApiError::Unauthorized("Incorrect public key or password".to_string()))?;
+    if !verify(password, &auth.hashed_password)? {
+        return Err(ApiError::Unauthorized(
+            "Incorrect public key or password".to_string(),
+        ));
+    }
+    Ok(auth)
+}
+
+fn hash_password(password: &str) -> Result<String, ApiError> {
+    Ok(hash(password, DEFAULT_COST)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(start: Option<&str>, sort: Option<&str>) -> PagingQuery {
+        PagingQuery {
+            start: start.map(str::to_string),
+            limit: None,
+            sort: sort.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn paging_by_key() {
+        let paging = query(Some("02ab"), Some("-public_key"))
+            .to_paging("public_key", &[SortField::Name])
+            .unwrap();
+        assert_eq!(paging.start.as_deref(), Some("02ab"));
+        assert!(paging.descending);
+        assert_eq!(paging.sort, None);
+    }
+
+    #[test]
+    fn paging_by_another_field() {
+        let fields = [SortField::Name, SortField::LastUpdated];
+        let paging = query(None, Some("name"))
+            .to_paging("public_key", &fields)
+            .unwrap();
+        assert_eq!(paging.sort, Some(database::Sort::Name(None)));
+        assert!(!paging.descending);
+
+        let paging = query(Some(r#"[1584000000, "02ab"]"#), Some("-last_updated"))
+            .to_paging("public_key", &fields)
+            .unwrap();
+        assert_eq!(paging.start.as_deref(), Some("02ab"));
+        assert_eq!(
+            paging.sort,
+            Some(database::Sort::LastUpdated(Some(1_584_000_000)))
+        );
+        assert!(paging.descending);
+    }
+
+    #[test]
+    fn paging_rejects_unknown_fields_and_positions() {
+        let fields = [SortField::LastUpdated];
+        assert!(query(None, Some("name"))
+            .to_paging("record_id", &fields)
+            .is_err());
+        // A key is not a position in a list sorted by another field
+        assert!(query(Some("fish-1"), Some("last_updated"))
+            .to_paging("record_id", &fields)
+            .is_err());
+        assert!(
+            query(Some(r#"["yesterday", "fish-1"]"#), Some("last_updated"))
+                .to_paging("record_id", &fields)
+                .is_err()
+        );
+        let mut zero = query(None, None);
+        zero.limit = Some(0);
+        assert!(zero.to_paging("record_id", &fields).is_err());
+    }
 }
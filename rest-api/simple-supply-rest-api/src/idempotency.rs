@@ -0,0 +1,534 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use actix_service::{Service, Transform};
+use actix_web::body::{Body, ResponseBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::error::ErrorNotFound;
+use actix_web::error::PayloadError;
+use actix_web::http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use actix_web::http::StatusCode;
+use actix_web::web::{Bytes, BytesMut};
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest, HttpResponse};
+use chrono::Utc;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use futures::future::{err, ok, Ready};
+use futures::{stream, StreamExt};
+use rustc_hex::ToHex;
+use serde_json::Value;
+
+use crate::authentication::AuthenticatedAgent;
+use crate::database;
+use crate::error::ApiError;
+use crate::messaging::SUBMIT_TIMEOUT;
+use crate::model::{IdempotencyKey, NewIdempotencyKey};
+
+/// Header a client sets to a unique value to make retries of a write safe
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+/// Header set on responses which were stored for an earlier request
+const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+/// Content type under which a `ReplayRecord` is stored in place of a body
+const REPLAY_RECORD_TYPE: &str = "application/x-idempotency-record+json";
+const MAX_KEY_LEN: usize = 255;
+/// How long a key is remembered, in seconds
+const KEY_LIFETIME: i64 = 24 * 60 * 60;
+/// How long a claim holds, in seconds, after which the request holding it
+/// can't still be waiting on the validator and another may take it over
+const CLAIM_LEASE: i64 = SUBMIT_TIMEOUT.as_secs() as i64 + 10;
+
+/// What to do with a request whose key has been looked up
+#[derive(Debug, PartialEq)]
+enum Claim {
+    /// The key is new, or its earlier claim has lapsed, so the request is
+    /// handled and its response stored
+    Claimed,
+    /// The key was used for the same request before, which gets the stored
+    /// response again
+    Replay(IdempotencyKey),
+}
+
+/// Middleware which remembers the responses to write requests carrying an
+/// `Idempotency-Key` header, so that a retried request returns the first
+/// result instead of submitting its transactions again. Keys are kept per
+/// agent, so it has to run inside `Authentication` where that applies.
+///
+/// Responses are stored as they are, unless the handler attached a
+/// `ReplayRecord` to its own. Routes which return tokens or keys do that, so
+/// that no secret is stored.
+#[derive(Clone)]
+pub struct Idempotency {
+    pool: Pool<ConnectionManager<PgConnection>>,
+    max_body_size: usize,
+    secret: Rc<Vec<u8>>,
+}
+
+impl Idempotency {
+    /// Bodies are read in full to hash them, so they are limited to
+    /// `max_body_size` bytes here rather than by their extractors. Requests
+    /// are hashed with an HMAC keyed by `secret`, so that stored hashes give
+    /// nothing away about the bodies.
+    pub fn new(
+        pool: Pool<ConnectionManager<PgConnection>>,
+        max_body_size: usize,
+        secret: &[u8],
+    ) -> Self {
+        Idempotency {
+            pool,
+            max_body_size,
+            secret: Rc::new(secret.to_vec()),
+        }
+    }
+}
+
+/// What a handler stores instead of its response, when that holds something
+/// which mustn't be kept. A replay calls the handler again with the record
+/// as its `Replay`, so that it can answer afresh.
+pub struct ReplayRecord(pub Value);
+
+/// The `ReplayRecord` stored by the first request with a key. Handlers
+/// which store one take an `Option<Replay>`, which is only set on a replay.
+#[derive(Clone, Debug)]
+pub struct Replay(pub Value);
+
+impl FromRequest for Replay {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.extensions().get::<Replay>() {
+            Some(replay) => ok(replay.clone()),
+            None => err(ErrorNotFound("Not a replayed request")),
+        }
+    }
+}
+
+impl<S> Transform<S> for Idempotency
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<Body>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<Body>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(IdempotencyMiddleware {
+            service: Rc::new(RefCell::new(service)),
+            pool: self.pool.clone(),
+            max_body_size: self.max_body_size,
+            secret: self.secret.clone(),
+        })
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    // Shared with the response future, which calls it once the request body
+    // has been read
+    service: Rc<RefCell<S>>,
+    pool: Pool<ConnectionManager<PgConnection>>,
+    max_body_size: usize,
+    secret: Rc<Vec<u8>>,
+}
+
+impl<S> Service for IdempotencyMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<Body>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<Body>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: ServiceRequest) -> Self::Future {
+        let key = match req.headers().get(IDEMPOTENCY_KEY) {
+            Some(key) if !req.method().is_safe() => key.to_str().ok().map(str::to_string),
+            _ => return Box::pin(self.service.borrow_mut().call(req)),
+        };
+        let key = match key {
+            Some(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key,
+            _ => {
+                return Box::pin(ok(req.error_response(ApiError::BadRequest(format!(
+                    "{} must be between 1 and {} visible characters",
+                    IDEMPOTENCY_KEY, MAX_KEY_LEN
+                )))))
+            }
+        };
+        let agent = req
+            .extensions()
+            .get::<AuthenticatedAgent>()
+            .map(|agent| agent.public_key.clone())
+            .unwrap_or_default();
+
+        let service = self.service.clone();
+        let pool = self.pool.clone();
+        let max_body_size = self.max_body_size;
+        let secret = self.secret.clone();
+        Box::pin(async move {
+            let mut body = BytesMut::new();
+            let mut payload = req.take_payload();
+            while let Some(chunk) = payload.next().await {
+                let chunk = chunk?;
+                if body.len() + chunk.len() > max_body_size {
+                    return Ok(req.error_response(ApiError::PayloadTooLarge(format!(
+                        "Request body is larger than {} bytes",
+                        max_body_size
+                    ))));
+                }
+                body.extend_from_slice(&chunk);
+            }
+            let body = body.freeze();
+
+            let now = Utc::now().timestamp();
+            let new_key = NewIdempotencyKey {
+                idempotency_key: key,
+                agent,
+                request_hash: hash_request(
+                    &secret,
+                    req.method().as_str(),
+                    &req.uri().to_string(),
+                    &body,
+                ),
+                created_at: now,
+                claimed_at: now,
+            };
+            let claimed_key = new_key.clone();
+            let claimed =
+                database::with_connection(&pool, move |conn| claim(conn, &claimed_key)).await;
+            let replayed = match claimed {
+                Ok(Claim::Claimed) => None,
+                Ok(Claim::Replay(stored)) => match stored_record(&stored) {
+                    Some(Ok(record)) => Some(record),
+                    Some(Err(err)) => return Ok(req.error_response(err)),
+                    None => return Ok(replay(req, stored)),
+                },
+                Err(err) => return Ok(req.error_response(err)),
+            };
+
+            // Hand the body on to the handler, which hasn't seen it yet
+            req.set_payload(Payload::Stream(Box::pin(stream::once(
+                ok::<_, PayloadError>(body),
+            ))));
+            if let Some(record) = replayed {
+                // The handler answers the replay from its record, and that
+                // answer is not stored in turn
+                req.extensions_mut().insert(record);
+                let response = service.borrow_mut().call(req);
+                let mut response = response.await?;
+                response.headers_mut().insert(
+                    HeaderName::from_static(IDEMPOTENT_REPLAYED),
+                    HeaderValue::from_static("true"),
+                );
+                return Ok(response);
+            }
+            let response = service.borrow_mut().call(req);
+            let response = match response.await {
+                Ok(response) => response,
+                Err(err) => {
+                    release(&pool, &new_key).await;
+                    return Err(err);
+                }
+            };
+            store(&pool, &new_key, response).await
+        })
+    }
+}
+
+/// Hashes everything that makes a request what it is, so that a key can't
+/// be reused for a different one
+fn hash_request(secret: &[u8], method: &str, uri: &str, body: &[u8]) -> String {
+    let mut hmac = Hmac::new(Sha256::new(), secret);
+    hmac.input(b"idempotency\n");
+    hmac.input(method.as_bytes());
+    hmac.input(b" ");
+    hmac.input(uri.as_bytes());
+    hmac.input(b"\n");
+    hmac.input(body);
+    hmac.result().code().to_hex()
+}
+
+fn claim(conn: &PgConnection, new_key: &NewIdempotencyKey) -> Result<Claim, ApiError> {
+    database::delete_expired_idempotency_keys(conn, new_key.created_at - KEY_LIFETIME)?;
+    if database::insert_idempotency_key(conn, new_key)? {
+        return Ok(Claim::Claimed);
+    }
+
+    let stored = database::fetch_idempotency_key(conn, &new_key.idempotency_key, &new_key.agent)?
+        .ok_or_else(|| {
+        ApiError::Conflict(format!(
+            "The request with this {} was just abandoned, try again",
+            IDEMPOTENCY_KEY
+        ))
+    })?;
+    match check_stored(&stored, &new_key.request_hash, new_key.claimed_at)? {
+        Claim::Claimed
+            if !database::take_over_idempotency_key(
+                conn,
+                &stored.idempotency_key,
+                &stored.agent,
+                stored.claimed_at,
+                new_key.claimed_at,
+            )? =>
+        {
+            Err(still_handled())
+        }
+        claim => Ok(claim),
+    }
+}
+
+/// Decides what to do with a request whose key an earlier one stored. Its
+/// claim may be taken over once the lease on it has run out, since the
+/// request which held it must have been dropped before it could finish.
+fn check_stored(stored: &IdempotencyKey, request_hash: &str, now: i64) -> Result<Claim, ApiError> {
+    if stored.request_hash != request_hash {
+        return Err(ApiError::Conflict(format!(
+            "This {} was already used for a different request",
+            IDEMPOTENCY_KEY
+        )));
+    }
+    if stored.status_code.is_some() {
+        return Ok(Claim::Replay(stored.clone()));
+    }
+    if stored.claimed_at + CLAIM_LEASE <= now {
+        return Ok(Claim::Claimed);
+    }
+    Err(still_handled())
+}
+
+fn still_handled() -> ApiError {
+    ApiError::Conflict(format!(
+        "The request with this {} is still being handled",
+        IDEMPOTENCY_KEY
+    ))
+}
+
+/// Forgets a key whose request failed without a lasting result, so that it
+/// can be retried
+async fn release(pool: &Pool<ConnectionManager<PgConnection>>, key: &NewIdempotencyKey) {
+    let (idempotency_key, agent) = (key.idempotency_key.clone(), key.agent.clone());
+    let deleted = database::with_connection(pool, move |conn| {
+        Ok(database::delete_idempotency_key(
+            conn,
+            &idempotency_key,
+            &agent,
+        )?)
+    })
+    .await;
+    if let Err(err) = deleted {
+        error!(
+            "Cannot release {} {}: {}",
+            IDEMPOTENCY_KEY, key.idempotency_key, err
+        );
+    }
+}
+
+/// Stores the response for replays. Server errors are not stored, since
+/// they may well not happen again.
+async fn store(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+    key: &NewIdempotencyKey,
+    mut response: ServiceResponse<Body>,
+) -> Result<ServiceResponse<Body>, Error> {
+    if response.status().is_server_error() {
+        release(pool, key).await;
+        return Ok(response);
+    }
+
+    let record = response
+        .response()
+        .extensions()
+        .get::<ReplayRecord>()
+        .map(|record| record.0.to_string());
+
+    let mut body = BytesMut::new();
+    let mut stream = response.take_body();
+    while let Some(chunk) = stream.next().await {
+        body.extend_from_slice(&chunk?);
+    }
+    let body: Bytes = body.freeze();
+
+    let (content_type, stored_body) = match record {
+        Some(record) => (Some(REPLAY_RECORD_TYPE.to_string()), Bytes::from(record)),
+        None => (
+            response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            body.clone(),
+        ),
+    };
+    let status_code = i32::from(response.status().as_u16());
+    let (idempotency_key, agent) = (key.idempotency_key.clone(), key.agent.clone());
+    let stored = database::with_connection(pool, move |conn| {
+        Ok(database::complete_idempotency_key(
+            conn,
+            &idempotency_key,
+            &agent,
+            status_code,
+            content_type.as_deref(),
+            &stored_body,
+        )?)
+    })
+    .await;
+    if let Err(err) = stored {
+        // The request went through, so its response is still returned
+        error!(
+            "Cannot store the response for {} {}: {}",
+            IDEMPOTENCY_KEY, key.idempotency_key, err
+        );
+        release(pool, key).await;
+    }
+
+    Ok(response.map_body(|_, _| ResponseBody::Body(Body::from(body))))
+}
+
+/// The `Replay` for a key whose handler stored a `ReplayRecord`, or `None`
+/// if its response was stored as it is
+fn stored_record(stored: &IdempotencyKey) -> Option<Result<Replay, ApiError>> {
+    if stored.content_type.as_deref() != Some(REPLAY_RECORD_TYPE) {
+        return None;
+    }
+    let body = stored.response_body.as_deref().unwrap_or_default();
+    Some(serde_json::from_slice(body).map(Replay).map_err(|err| {
+        ApiError::InternalError(format!(
+            "Stored record for {} {} is invalid: {}",
+            IDEMPOTENCY_KEY, stored.idempotency_key, err
+        ))
+    }))
+}
+
+fn replay(req: ServiceRequest, stored: IdempotencyKey) -> ServiceResponse<Body> {
+    let status = stored
+        .status_code
+        .and_then(|status| StatusCode::from_u16(status as u16).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = HttpResponse::build(status);
+    if let Some(content_type) = stored.content_type {
+        response.content_type(content_type);
+    }
+    req.into_response(
+        response
+            .header(IDEMPOTENT_REPLAYED, "true")
+            .body(stored.response_body.unwrap_or_default()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"idempotency-test";
+    const NOW: i64 = 1_600_000_000;
+
+    fn stored(status_code: Option<i32>, claimed_at: i64) -> IdempotencyKey {
+        IdempotencyKey {
+            idempotency_key: "key".to_string(),
+            agent: "agent".to_string(),
+            request_hash: hash_request(SECRET, "POST", "/records", b"{}"),
+            status_code,
+            content_type: status_code.map(|_| "application/json".to_string()),
+            response_body: status_code.map(|_| b"{}".to_vec()),
+            created_at: claimed_at,
+            claimed_at,
+        }
+    }
+
+    fn is_conflict(result: Result<Claim, ApiError>) -> bool {
+        matches!(result, Err(ApiError::Conflict(_)))
+    }
+
+    #[test]
+    fn requests_are_hashed_with_the_secret() {
+        let hash = hash_request(SECRET, "POST", "/records", b"{}");
+        assert_eq!(hash, hash_request(SECRET, "POST", "/records", b"{}"));
+        assert_ne!(hash, hash_request(b"other", "POST", "/records", b"{}"));
+        assert_ne!(hash, hash_request(SECRET, "PATCH", "/records", b"{}"));
+        assert_ne!(
+            hash,
+            hash_request(SECRET, "POST", "/records?wait=false", b"{}")
+        );
+        assert_ne!(hash, hash_request(SECRET, "POST", "/records", b"{ }"));
+    }
+
+    #[test]
+    fn finished_requests_are_replayed() {
+        let stored = stored(Some(201), NOW - 5);
+        assert_eq!(
+            check_stored(&stored, &stored.request_hash, NOW).unwrap(),
+            Claim::Replay(stored.clone())
+        );
+        // However long ago they finished
+        assert_eq!(
+            check_stored(&stored, &stored.request_hash, NOW + CLAIM_LEASE).unwrap(),
+            Claim::Replay(stored.clone())
+        );
+    }
+
+    #[test]
+    fn keys_cannot_be_reused_for_other_requests() {
+        let other = hash_request(SECRET, "POST", "/records", b"{\"record_id\":\"2\"}");
+        assert!(is_conflict(check_stored(
+            &stored(Some(201), NOW),
+            &other,
+            NOW
+        )));
+        assert!(is_conflict(check_stored(&stored(None, NOW), &other, NOW)));
+        assert!(is_conflict(check_stored(
+            &stored(None, NOW - CLAIM_LEASE),
+            &other,
+            NOW
+        )));
+    }
+
+    #[test]
+    fn claims_hold_until_their_lease_runs_out() {
+        let held = stored(None, NOW - CLAIM_LEASE + 1);
+        assert!(is_conflict(check_stored(&held, &held.request_hash, NOW)));
+
+        let stale = stored(None, NOW - CLAIM_LEASE);
+        assert_eq!(
+            check_stored(&stale, &stale.request_hash, NOW).unwrap(),
+            Claim::Claimed
+        );
+    }
+
+    #[test]
+    fn the_lease_outlasts_a_submission() {
+        assert!(CLAIM_LEASE as u64 > SUBMIT_TIMEOUT.as_secs());
+    }
+
+    #[test]
+    fn only_records_are_handed_back_to_the_handler() {
+        assert!(stored_record(&stored(Some(201), NOW)).is_none());
+
+        let mut record = stored(Some(201), NOW);
+        record.content_type = Some(REPLAY_RECORD_TYPE.to_string());
+        record.response_body = Some(br#"{"public_key":"02ab"}"#.to_vec());
+        match stored_record(&record) {
+            Some(Ok(Replay(value))) => assert_eq!(value["public_key"], "02ab"),
+            other => panic!("expected a record, got {:?}", other),
+        }
+
+        record.response_body = Some(b"not json".to_vec());
+        assert!(matches!(
+            stored_record(&record),
+            Some(Err(ApiError::InternalError(_)))
+        ));
+    }
+}
@@ -2,21 +2,36 @@
 extern crate log;
 #[macro_use]
 extern crate diesel;
+#[macro_use]
+extern crate clap;
 
 use actix_service::ServiceFactory;
 use actix_web::body::Body;
 use actix_web::dev::{ServiceRequest, ServiceResponse};
 use actix_web::error::Error;
-use actix_web::{web, App, HttpServer};
+use actix_web::{guard, web, App, HttpServer};
+use authentication::Authentication;
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
 use dotenv::dotenv;
-use std::env;
+use std::process;
 
+pub mod authentication;
+pub mod batch_validation;
+pub mod config;
 pub mod database;
+pub mod encryption;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod geojson;
 pub mod handler;
+pub mod idempotency;
 pub mod messaging;
+pub mod metrics;
 pub mod model;
+pub mod openapi;
+pub mod rate_limit;
 pub mod schema;
 pub mod transaction_creation;
 
@@ -24,19 +39,41 @@ pub mod transaction_creation;
 pub struct Server {
     messenger: messaging::Messenger,
     pool: Pool<ConnectionManager<PgConnection>>,
+    events: events::EventBroker,
+    metrics: metrics::Metrics,
+    rate_limiter: rate_limit::RateLimiter,
+    jwt_secret: String,
+    idempotency_secret: String,
+    aes_key: Vec<u8>,
+    max_json_size: usize,
+    max_batch_size: usize,
 }
 
 impl Server {
-    pub fn new() -> Self {
-        let validator_url = env::var("VALIDATOR_URL").expect("VALIDATOR_URL is not set");
-        let messenger = messaging::Messenger::new(validator_url);
+    pub fn new(settings: &config::Settings) -> Self {
+        let metrics = metrics::Metrics::default();
+        let messenger = messaging::Messenger::new(settings.validator_url.clone(), metrics.clone());
 
-        let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-        let manager = ConnectionManager::<PgConnection>::new(database_url);
+        let events = events::EventBroker::listen(settings.database_url.clone());
+        let manager = ConnectionManager::<PgConnection>::new(settings.database_url.as_str());
         let pool = Pool::builder()
+            .max_size(settings.pool_size)
             .build(manager)
             .expect("Failed to create pool.");
-        Server { messenger, pool }
+
+        let aes_key = encryption::parse_aes_key(&settings.aes_key).expect("Invalid AES key");
+        Server {
+            messenger,
+            pool,
+            events,
+            metrics,
+            rate_limiter: rate_limit::RateLimiter::new(settings.rate_limits.clone()),
+            jwt_secret: settings.jwt_secret.clone(),
+            idempotency_secret: settings.idempotency_secret.clone(),
+            aes_key,
+            max_json_size: settings.max_json_size,
+            max_batch_size: settings.max_batch_size,
+        }
     }
 }
 
@@ -54,21 +91,231 @@ pub fn app(
     >,
     Body,
 > {
-    use crate::handler::*;
+    use crate::handler::not_found;
 
+    let pool = server.pool.clone();
+    let authentication = Authentication::new(&server.jwt_secret, move |public_key| {
+        let pool = pool.clone();
+        async move {
+            database::with_connection(&pool, move |conn| {
+                Ok(database::fetch_token_version(conn, &public_key)?)
+            })
+            .await
+        }
+    });
+    let idempotency = idempotency::Idempotency::new(
+        server.pool.clone(),
+        server.max_json_size.max(server.max_batch_size),
+        server.idempotency_secret.as_bytes(),
+    );
+    let rate_limiter = server.rate_limiter.clone();
+    let request_metrics = metrics::RequestMetrics::new(server.metrics.clone());
+    let json_config = web::JsonConfig::default()
+        .limit(server.max_json_size)
+        .error_handler(error::json_error);
     App::new()
         .data(server)
-        .service(web::resource("/agents").route(web::post().to(create_agent)))
+        .wrap(request_metrics)
+        .app_data(json_config)
+        .app_data(web::QueryConfig::default().error_handler(error::bad_request))
+        .app_data(web::PathConfig::default().error_handler(error::bad_request))
+        .default_service(web::route().to(not_found))
+        .configure(|cfg| routes(cfg, authentication, idempotency, rate_limiter))
+}
+
+/// Registers every route of the API. Keep `openapi::document` in step with
+/// this, which its test checks.
+pub fn routes(
+    cfg: &mut web::ServiceConfig,
+    authentication: Authentication,
+    idempotency: idempotency::Idempotency,
+    rate_limiter: rate_limit::RateLimiter,
+) {
+    use crate::handler::*;
+
+    let limit = |route| rate_limit::RateLimit::new(rate_limiter.clone(), route);
+    let limit_address = |route| rate_limit::RateLimit::by_address(rate_limiter.clone(), route);
+
+    // Middleware wrapped last runs first: rate limiting by address, then
+    // authentication, then rate limiting by agent, then idempotency. It goes
+    // on each resource rather than a scope, so that requests it rejects are
+    // still labelled by their route in the metrics.
+    //
+    // Agents, records and their history are only readable with a token.
+    // Signing up, logging in, self-signed batches with their statuses and
+    // the operational endpoints are open.
+    cfg.service(
+        // Listing agents needs a token but creating one can't, so each has
+        // its own resource and the guard passes other methods on
+        web::resource("/agents")
+            .guard(guard::Get())
+            .wrap(limit("/agents"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/agents"))
+            .route(web::get().to(list_agents)),
+    )
+    .service(
+        web::resource("/agents")
+            .wrap(idempotency.clone())
+            .wrap(limit("/agents"))
+            .route(web::post().to(create_agent)),
+    )
+    .service(
+        web::resource("/agents/{public_key}")
+            .wrap(limit("/agents/{public_key}"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/agents/{public_key}"))
+            .route(web::get().to(fetch_agent)),
+    )
+    .service(
+        web::resource("/health")
+            .wrap(limit("/health"))
+            .route(web::get().to(check_health)),
+    )
+    .service(
+        web::resource("/ready")
+            .wrap(limit("/ready"))
+            .route(web::get().to(check_readiness)),
+    )
+    .service(
+        web::resource("/metrics")
+            .wrap(limit("/metrics"))
+            .route(web::get().to(render_metrics)),
+    )
+    .service(
+        web::resource("/openapi.json")
+            .wrap(limit("/openapi.json"))
+            .route(web::get().to(fetch_openapi)),
+    )
+    .service(
+        web::resource("/authentication")
+            .wrap(limit("/authentication"))
+            .route(web::post().to(authenticate)),
+    )
+    .service(
+        web::resource("/authentication/password")
+            .wrap(limit("/authentication/password"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/authentication/password"))
+            .route(web::post().to(change_password)),
+    )
+    .service(
+        web::resource("/authentication/private_key")
+            .wrap(limit("/authentication/private_key"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/authentication/private_key"))
+            .route(web::post().to(export_private_key))
+            .route(web::delete().to(delete_private_key)),
+    )
+    .service(
+        web::resource("/batches")
+            .wrap(idempotency.clone())
+            .wrap(limit("/batches"))
+            .route(web::post().to(submit_batches)),
+    )
+    .service(
+        web::resource("/batch_statuses")
+            .wrap(limit("/batch_statuses"))
+            .route(web::get().to(fetch_batch_statuses)),
+    )
+    .service(
+        web::resource("/events")
+            .wrap(limit("/events"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/events"))
+            .route(web::get().to(record_events)),
+    )
+    .service(
+        web::resource("/export/{table}")
+            .wrap(limit("/export/{table}"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/export/{table}"))
+            .route(web::get().to(export_table)),
+    )
+    .service(
+        web::resource("/records.geojson")
+            .wrap(limit("/records.geojson"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records.geojson"))
+            .route(web::get().to(list_record_positions)),
+    )
+    .service(
+        web::resource("/records")
+            .wrap(idempotency.clone())
+            .wrap(limit("/records"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records"))
+            .route(web::get().to(list_records))
+            .route(web::post().to(create_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}")
+            .wrap(idempotency.clone())
+            .wrap(limit("/records/{record_id}"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records/{record_id}"))
+            .route(web::get().to(fetch_record))
+            .route(web::patch().to(update_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}/transfer")
+            .wrap(idempotency.clone())
+            .wrap(limit("/records/{record_id}/transfer"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records/{record_id}/transfer"))
+            .route(web::post().to(transfer_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}/assemble")
+            .wrap(idempotency.clone())
+            .wrap(limit("/records/{record_id}/assemble"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records/{record_id}/assemble"))
+            .route(web::post().to(assemble_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}/disassemble")
+            .wrap(idempotency.clone())
+            .wrap(limit("/records/{record_id}/disassemble"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records/{record_id}/disassemble"))
+            .route(web::post().to(disassemble_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}/recall")
+            .wrap(idempotency)
+            .wrap(limit("/records/{record_id}/recall"))
+            .wrap(authentication.clone())
+            .wrap(limit_address("/records/{record_id}/recall"))
+            .route(web::post().to(recall_record)),
+    )
+    .service(
+        web::resource("/records/{record_id}/track.geojson")
+            .wrap(limit("/records/{record_id}/track.geojson"))
+            .wrap(authentication)
+            .route(web::get().to(fetch_record_track)),
+    );
 }
 
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    env_logger::init();
+    let settings = match config::Settings::from_args() {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("Configuration error: {}", err);
+            process::exit(1);
+        }
+    };
+    // RUST_LOG can still adjust the level of individual modules
+    env_logger::Builder::from_default_env()
+        .filter_level(settings.log_level)
+        .init();
 
-    let server = Server::new();
+    let server = Server::new(&settings);
+    info!("Serving the API on {}", settings.bind);
     HttpServer::new(move || app(server.clone()))
-        .bind("localhost:3000")?
+        .bind(&settings.bind)?
         .run()
         .await
 }
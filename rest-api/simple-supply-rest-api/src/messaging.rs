@@ -1,52 +1,202 @@
-use crate::transaction_creation::make_create_agent_transaction;
+use crate::metrics::Metrics;
+use crate::transaction_creation::{
+    make_assemble_record_transaction, make_create_agent_transaction,
+    make_create_record_transaction, make_disassemble_record_transaction,
+    make_recall_record_transaction, make_transfer_record_transaction,
+    make_update_record_transaction, BatchBuilder,
+};
 
 use std::boxed::Box;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Duration;
 
-use protobuf::Message;
+use actix_rt::time::timeout;
+use futures::channel::oneshot;
+use protobuf::Message as _;
+use protos::payload::{PropertyBound, PropertyValue};
+use protos::record::{Record, RecordContainer};
 use sawtooth_sdk::messages::batch::Batch;
 use sawtooth_sdk::messages::client_batch_submit::{
-    ClientBatchStatusRequest, ClientBatchStatusResponse, ClientBatchStatusResponse_Status,
-    ClientBatchSubmitRequest,
+    ClientBatchStatus, ClientBatchStatusRequest, ClientBatchStatusResponse,
+    ClientBatchStatusResponse_Status, ClientBatchStatus_Status, ClientBatchSubmitRequest,
+    ClientBatchSubmitResponse, ClientBatchSubmitResponse_Status,
+};
+use sawtooth_sdk::messages::client_state::{
+    ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status,
+};
+use sawtooth_sdk::messages::network::{PingRequest, PingResponse};
+use sawtooth_sdk::messages::validator::{self, Message};
+use sawtooth_sdk::messaging::stream::{
+    MessageConnection, MessageReceiver, MessageSender, SendError,
 };
-use sawtooth_sdk::messages::validator;
-use sawtooth_sdk::messaging::stream::{MessageConnection, MessageFuture, MessageSender, SendError};
 use sawtooth_sdk::messaging::zmq_stream::{ZmqMessageConnection, ZmqMessageSender};
-use sawtooth_sdk::signing::{create_context, CryptoFactory, PrivateKey, PublicKey};
+use sawtooth_sdk::signing::{create_context, CryptoFactory, PrivateKey, PublicKey, Signer};
+use simple_supply_tp::addresser;
 use uuid::Uuid;
 
+/// How long to wait for the validator to answer a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the validator may take to answer a ping before it is considered
+/// unavailable
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long the validator may hold a batch status request open while waiting
+/// for the batches to commit
+const COMMIT_TIMEOUT_SECS: u32 = 30;
+/// The longest a handler waits on the validator to submit a batch and see it
+/// committed
+pub const SUBMIT_TIMEOUT: Duration =
+    Duration::from_secs(2 * REQUEST_TIMEOUT.as_secs() + COMMIT_TIMEOUT_SECS as u64);
+
+#[derive(Debug)]
+pub enum MessagingError {
+    /// The validator could not be reached or did not reply in time
+    SendError(SendError),
+    /// The validator replied with an error or a reply we could not read
+    ValidatorError(String),
+    /// A batch was rejected, with the reason given by the transaction
+    /// processor
+    InvalidTransaction(String),
+    /// A batch was still pending when we stopped waiting for it
+    NotCommitted(String),
+}
+
+impl fmt::Display for MessagingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessagingError::SendError(err) => write!(f, "cannot reach the validator: {}", err),
+            MessagingError::ValidatorError(msg) => write!(f, "validator error: {}", msg),
+            MessagingError::InvalidTransaction(msg) => write!(f, "invalid transaction: {}", msg),
+            MessagingError::NotCommitted(batch_id) => {
+                write!(f, "batch {} has not been committed yet", batch_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MessagingError {}
+
+impl From<SendError> for MessagingError {
+    fn from(err: SendError) -> Self {
+        MessagingError::SendError(err)
+    }
+}
+
+type PendingReplies = Arc<Mutex<HashMap<String, oneshot::Sender<Message>>>>;
+
+/// A connection to the validator which can be awaited from async handlers.
+/// Replies are read on a dedicated thread and handed to the waiting request
+/// by correlation id.
 #[derive(Clone)]
 pub struct Connection {
     sender: ZmqMessageSender,
+    pending: PendingReplies,
 }
 
 impl Connection {
     pub fn new(validator_url: String) -> Connection {
         let connection = ZmqMessageConnection::new(&validator_url);
-        let (sender, _) = connection.create();
-        Connection { sender }
+        let (sender, receiver) = connection.create();
+        let pending = PendingReplies::default();
+
+        let dispatch_sender = sender.clone();
+        let dispatch_pending = pending.clone();
+        thread::Builder::new()
+            .name("validator-replies".to_string())
+            .spawn(move || dispatch_replies(receiver, dispatch_sender, dispatch_pending))
+            .expect("Failed to start validator reply thread");
+
+        Connection { sender, pending }
     }
 
-    pub fn send(
+    pub async fn request(
         &self,
         message_type: validator::Message_MessageType,
-        correlation_id: &str,
         message_content: &[u8],
-    ) -> Result<MessageFuture, SendError> {
-        self.sender
-            .send(message_type, correlation_id, message_content)
+        timeout_after: Duration,
+    ) -> Result<Message, SendError> {
+        let correlation_id = Uuid::new_v4().to_hyphenated().to_string();
+        let (reply_sender, reply_receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(correlation_id.clone(), reply_sender);
+
+        // Unlike `send`, `reply` doesn't register the correlation id with the
+        // SDK, so the response is delivered to our receiver thread instead
+        if let Err(err) = self
+            .sender
+            .reply(message_type, &correlation_id, message_content)
+        {
+            self.pending.lock().unwrap().remove(&correlation_id);
+            return Err(err);
+        }
+
+        match timeout(timeout_after, reply_receiver).await {
+            Ok(Ok(message)) => Ok(message),
+            Ok(Err(_)) => Err(SendError::DisconnectedError),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&correlation_id);
+                Err(SendError::TimeoutError)
+            }
+        }
     }
 }
 
+fn dispatch_replies(receiver: MessageReceiver, sender: ZmqMessageSender, pending: PendingReplies) {
+    for result in receiver.iter() {
+        match result {
+            Ok(message) => {
+                let reply_sender = pending.lock().unwrap().remove(message.get_correlation_id());
+                match reply_sender {
+                    // The request may have timed out in the meantime
+                    Some(reply_sender) => reply_sender.send(message).unwrap_or(()),
+                    // The validator pings idle connections and closes them
+                    // if they don't answer
+                    None if message.get_message_type()
+                        == validator::Message_MessageType::PING_REQUEST =>
+                    {
+                        let response = PingResponse::new().write_to_bytes().unwrap();
+                        if let Err(err) = sender.reply(
+                            validator::Message_MessageType::PING_RESPONSE,
+                            message.get_correlation_id(),
+                            &response,
+                        ) {
+                            warn!("Failed to answer validator ping: {:?}", err);
+                        }
+                    }
+                    None => debug!(
+                        "Dropping unexpected message {}",
+                        message.get_correlation_id()
+                    ),
+                }
+            }
+            Err(err) => warn!("Failed to receive from validator: {:?}", err),
+        }
+    }
+
+    // Dropping the senders fails any requests still waiting
+    pending.lock().unwrap().clear();
+}
+
+fn parse_reply<T: protobuf::Message>(message: &Message) -> Result<T, MessagingError> {
+    protobuf::parse_from_bytes(message.get_content())
+        .map_err(|err| MessagingError::ValidatorError(format!("malformed reply: {}", err)))
+}
+
 #[derive(Clone)]
 pub struct Messenger {
     connection: Connection,
+    metrics: Metrics,
 }
 
 impl Messenger {
-    pub fn new(validator_url: String) -> Messenger {
+    pub fn new(validator_url: String, metrics: Metrics) -> Messenger {
         Messenger {
             connection: Connection::new(validator_url),
+            metrics,
         }
     }
     pub fn get_new_key_pair(&self) -> (Box<dyn PrivateKey>, Box<dyn PublicKey>) {
@@ -56,80 +206,444 @@ impl Messenger {
         (private_key, public_key)
     }
 
-    pub fn send_create_agent_transaction(
+    pub async fn send_create_agent_transaction(
         &self,
         private_key: &dyn PrivateKey,
         name: &str,
         timestamp: i64,
-    ) -> Result<(), SendError> {
-        let context = create_context("secp256k1").unwrap();
-        let crypto_factory = CryptoFactory::new(&*context);
-        let transaction_signer = crypto_factory.new_signer(&*private_key);
-        let random_private_key = context.new_random_private_key().unwrap();
-        let batch_signer = crypto_factory.new_signer(&*random_private_key); // TODO ここはMessengerの中に入れる?
-        let batch =
-            make_create_agent_transaction(transaction_signer, batch_signer, name, timestamp);
-        self.send_and_wait_for_commit(batch) // await
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_create_agent_transaction(transaction_signer, batch_signer, name, timestamp)
+        })
+        .await
+    }
+
+    pub async fn send_create_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        latitude: i64,
+        longitude: i64,
+        bounds: Vec<PropertyBound>,
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_create_record_transaction(
+                transaction_signer,
+                batch_signer,
+                record_id,
+                latitude,
+                longitude,
+                bounds,
+                timestamp,
+            )
+        })
+        .await
+    }
+
+    pub async fn send_update_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        latitude: i64,
+        longitude: i64,
+        properties: Vec<PropertyValue>,
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_update_record_transaction(
+                transaction_signer,
+                batch_signer,
+                record_id,
+                latitude,
+                longitude,
+                properties,
+                timestamp,
+            )
+        })
+        .await
+    }
+
+    pub async fn send_transfer_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        receiving_agent: &str,
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_transfer_record_transaction(
+                transaction_signer,
+                batch_signer,
+                record_id,
+                receiving_agent,
+                timestamp,
+            )
+        })
+        .await
+    }
+
+    pub async fn send_assemble_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        component_ids: &[String],
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_assemble_record_transaction(
+                transaction_signer,
+                batch_signer,
+                record_id,
+                component_ids,
+                timestamp,
+            )
+        })
+        .await
+    }
+
+    pub async fn send_disassemble_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        component_ids: &[String],
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_disassemble_record_transaction(
+                transaction_signer,
+                batch_signer,
+                record_id,
+                component_ids,
+                timestamp,
+            )
+        })
+        .await
+    }
+
+    pub async fn send_recall_record_transaction(
+        &self,
+        private_key: &dyn PrivateKey,
+        record_id: &str,
+        timestamp: i64,
+    ) -> Result<String, MessagingError> {
+        self.sign_and_submit(private_key, |transaction_signer, batch_signer| {
+            make_recall_record_transaction(transaction_signer, batch_signer, record_id, timestamp)
+        })
+        .await
     }
 
-    pub fn send_and_wait_for_commit(&self, batch: Batch) -> Result<(), SendError> {
+    /// Signs the transactions that `add_transactions` adds to the builder with
+    /// `private_key`, submits them as a single batch and waits for it to
+    /// commit. The validator applies all of them or none of them. Returns the
+    /// batch id.
+    pub async fn send_transactions_and_wait<F>(
+        &self,
+        private_key: &dyn PrivateKey,
+        add_transactions: F,
+    ) -> Result<String, MessagingError>
+    where
+        F: FnOnce(&mut BatchBuilder, &Signer),
+    {
+        let batch_id = self
+            .sign_and_submit(private_key, |transaction_signer, batch_signer| {
+                let mut builder = BatchBuilder::new(batch_signer);
+                add_transactions(&mut builder, &transaction_signer);
+                builder.build()
+            })
+            .await?;
+        self.wait_for_commit(&batch_id).await?;
+        Ok(batch_id)
+    }
+
+    /// Signs the transactions built by `make_batch` with `private_key` and the
+    /// batch with a throwaway key, then submits it without waiting for it to
+    /// commit. Returns the batch id.
+    async fn sign_and_submit<F>(
+        &self,
+        private_key: &dyn PrivateKey,
+        make_batch: F,
+    ) -> Result<String, MessagingError>
+    where
+        F: FnOnce(Signer, Signer) -> Batch,
+    {
+        let batch = {
+            let context = create_context("secp256k1").unwrap();
+            let crypto_factory = CryptoFactory::new(&*context);
+            let transaction_signer = crypto_factory.new_signer(private_key);
+            let random_private_key = context.new_random_private_key().unwrap();
+            let batch_signer = crypto_factory.new_signer(&*random_private_key); // TODO ここはMessengerの中に入れる?
+            make_batch(transaction_signer, batch_signer)
+        };
+        let batch_id = batch.get_header_signature().to_string();
+        self.submit_batches(vec![batch]).await?;
+        Ok(batch_id)
+    }
+
+    /// Reads a record from the current validator state
+    pub async fn fetch_record(&self, record_id: &str) -> Result<Option<Record>, MessagingError> {
+        let request = ClientStateGetRequest {
+            address: addresser::get_record_address(record_id),
+            ..Default::default()
+        };
+        let request_bytes = request.write_to_bytes().unwrap();
+
+        let reply = self
+            .connection
+            .request(
+                validator::Message_MessageType::CLIENT_STATE_GET_REQUEST,
+                &request_bytes,
+                REQUEST_TIMEOUT,
+            )
+            .await?;
+        let response: ClientStateGetResponse = parse_reply(&reply)?;
+
+        match response.get_status() {
+            ClientStateGetResponse_Status::OK => {
+                let container: RecordContainer = protobuf::parse_from_bytes(response.get_value())
+                    .map_err(|err| {
+                    MessagingError::ValidatorError(format!("malformed record: {}", err))
+                })?;
+                Ok(container
+                    .get_entries()
+                    .iter()
+                    .find(|record| record.get_record_id() == record_id)
+                    .cloned())
+            }
+            ClientStateGetResponse_Status::NO_RESOURCE => Ok(None),
+            status => Err(MessagingError::ValidatorError(format!(
+                "state request failed with {:?}",
+                status
+            ))),
+        }
+    }
+
+    /// Checks that the validator is connected and answering
+    pub async fn ping(&self) -> Result<(), MessagingError> {
+        let request_bytes = PingRequest::new().write_to_bytes().unwrap();
+        let reply = self
+            .connection
+            .request(
+                validator::Message_MessageType::PING_REQUEST,
+                &request_bytes,
+                PING_TIMEOUT,
+            )
+            .await?;
+        match reply.get_message_type() {
+            validator::Message_MessageType::PING_RESPONSE => Ok(()),
+            message_type => Err(MessagingError::ValidatorError(format!(
+                "unexpected reply to ping: {:?}",
+                message_type
+            ))),
+        }
+    }
+
+    /// Submits batches to the validator without waiting for them to commit
+    pub async fn submit_batches(&self, batches: Vec<Batch>) -> Result<(), MessagingError> {
+        let batch_count = batches.len();
+        let result = self.send_batches(batches).await;
+        match result {
+            Ok(()) => self.metrics.batches_submitted.inc_by(batch_count as _),
+            Err(_) => self.metrics.batch_submit_failures.inc_by(batch_count as _),
+        }
+        result
+    }
+
+    async fn send_batches(&self, batches: Vec<Batch>) -> Result<(), MessagingError> {
         let submit_request = ClientBatchSubmitRequest {
-            batches: ::protobuf::RepeatedField::from_vec(vec![batch.clone()]),
+            batches: ::protobuf::RepeatedField::from_vec(batches),
             ..Default::default()
         };
         let submit_request_bytes = submit_request.write_to_bytes().unwrap();
 
-        let mut future = match self.connection.send(
-            validator::Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST,
-            &Uuid::new_v4().to_hyphenated().to_string(),
-            &submit_request_bytes,
-        ) {
-            Ok(fut) => fut,
-            Err(_) => return Err(SendError::UnknownError),
-        };
-        loop {
-            match future.get_timeout(Duration::from_millis(10000)) {
-                Ok(_) => break,
-                Err(_) => {
-                    return Err(SendError::UnknownError);
-                }
-            };
+        let reply = self
+            .connection
+            .request(
+                validator::Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST,
+                &submit_request_bytes,
+                REQUEST_TIMEOUT,
+            )
+            .await?;
+        let submit_response: ClientBatchSubmitResponse = parse_reply(&reply)?;
+
+        match submit_response.get_status() {
+            ClientBatchSubmitResponse_Status::OK => Ok(()),
+            ClientBatchSubmitResponse_Status::INVALID_BATCH => Err(
+                MessagingError::InvalidTransaction("the batch was rejected as invalid".to_string()),
+            ),
+            status => Err(MessagingError::ValidatorError(format!(
+                "batch submission failed with {:?}",
+                status
+            ))),
         }
+    }
 
-        let batch_id = batch.header_signature;
+    /// Resolves once a submitted batch has committed, failing with the
+    /// processor's message if it turns out to be invalid
+    pub async fn wait_for_commit(&self, batch_id: &str) -> Result<(), MessagingError> {
+        let statuses = self
+            .fetch_batch_statuses(vec![batch_id.to_string()], true)
+            .await?;
+        let status = statuses.first().ok_or_else(|| {
+            MessagingError::ValidatorError(format!("no status for batch {}", batch_id))
+        })?;
+        match status.get_status() {
+            ClientBatchStatus_Status::COMMITTED => Ok(()),
+            ClientBatchStatus_Status::INVALID => Err(MessagingError::InvalidTransaction(
+                status
+                    .get_invalid_transactions()
+                    .first()
+                    .map(|transaction| transaction.get_message().to_string())
+                    .filter(|message| !message.is_empty())
+                    .unwrap_or_else(|| format!("batch {} is invalid", batch_id)),
+            )),
+            ClientBatchStatus_Status::PENDING => {
+                Err(MessagingError::NotCommitted(batch_id.to_string()))
+            }
+            _ => Err(MessagingError::ValidatorError(format!(
+                "batch {} is unknown to the validator",
+                batch_id
+            ))),
+        }
+    }
+
+    /// Looks up the status of submitted batches. When `wait` is set the
+    /// validator holds the reply until they are committed or invalid, or its
+    /// own timeout passes.
+    pub async fn fetch_batch_statuses(
+        &self,
+        batch_ids: Vec<String>,
+        wait: bool,
+    ) -> Result<Vec<ClientBatchStatus>, MessagingError> {
         let status_request = ClientBatchStatusRequest {
-            batch_ids: ::protobuf::RepeatedField::from_vec(vec![batch_id]),
-            wait: true,
+            batch_ids: ::protobuf::RepeatedField::from_vec(batch_ids),
+            wait,
+            timeout: if wait { COMMIT_TIMEOUT_SECS } else { 0 },
             ..Default::default()
         };
         let status_request_bytes = status_request.write_to_bytes().unwrap();
 
-        let mut future = match self.connection.send(
-            validator::Message_MessageType::CLIENT_BATCH_STATUS_REQUEST,
-            &Uuid::new_v4().to_hyphenated().to_string(),
-            &status_request_bytes,
-        ) {
-            Ok(fut) => fut,
-            Err(_) => return Err(SendError::UnknownError),
+        let reply_timeout = if wait {
+            REQUEST_TIMEOUT + Duration::from_secs(u64::from(COMMIT_TIMEOUT_SECS))
+        } else {
+            REQUEST_TIMEOUT
         };
+        let reply = self
+            .connection
+            .request(
+                validator::Message_MessageType::CLIENT_BATCH_STATUS_REQUEST,
+                &status_request_bytes,
+                reply_timeout,
+            )
+            .await?;
+        let status_response: ClientBatchStatusResponse = parse_reply(&reply)?;
+
+        match status_response.get_status() {
+            ClientBatchStatusResponse_Status::OK => Ok(status_response.batch_statuses.into_vec()),
+            status => Err(MessagingError::ValidatorError(format!(
+                "batch status request failed with {:?}",
+                status
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sawtooth_sdk::messages::batch::BatchHeader;
+    use sawtooth_sdk::messages::transaction::TransactionHeader;
+    use std::sync::mpsc::{channel, Receiver};
 
-        let status_response: ClientBatchStatusResponse;
-        loop {
-            match future.get_timeout(Duration::from_millis(10000)) {
-                Ok(validator_response) => {
-                    status_response =
-                        protobuf::parse_from_bytes(&validator_response.content[..]).unwrap();
-                    break;
+    /// Binds a socket that answers like a validator which commits every batch
+    /// it is given, and hands over the batches it receives
+    fn fake_validator() -> (String, Receiver<Batch>) {
+        let context = zmq::Context::new();
+        let socket = context.socket(zmq::ROUTER).unwrap();
+        socket.bind("tcp://127.0.0.1:*").unwrap();
+        let url = socket.get_last_endpoint().unwrap().unwrap();
+        let (batch_sender, batch_receiver) = channel();
+
+        thread::spawn(move || loop {
+            let parts = socket.recv_multipart(0).unwrap();
+            let request: Message = protobuf::parse_from_bytes(&parts[1]).unwrap();
+            let (message_type, content) = match request.get_message_type() {
+                validator::Message_MessageType::CLIENT_BATCH_SUBMIT_REQUEST => {
+                    let submit: ClientBatchSubmitRequest =
+                        protobuf::parse_from_bytes(request.get_content()).unwrap();
+                    for batch in submit.get_batches() {
+                        batch_sender.send(batch.clone()).unwrap_or(());
+                    }
+                    let response = ClientBatchSubmitResponse {
+                        status: ClientBatchSubmitResponse_Status::OK,
+                        ..Default::default()
+                    };
+                    (
+                        validator::Message_MessageType::CLIENT_BATCH_SUBMIT_RESPONSE,
+                        response.write_to_bytes().unwrap(),
+                    )
                 }
-                Err(_) => {
-                    return Err(SendError::UnknownError);
+                validator::Message_MessageType::CLIENT_BATCH_STATUS_REQUEST => {
+                    let status_request: ClientBatchStatusRequest =
+                        protobuf::parse_from_bytes(request.get_content()).unwrap();
+                    let statuses = status_request
+                        .get_batch_ids()
+                        .iter()
+                        .map(|batch_id| ClientBatchStatus {
+                            batch_id: batch_id.clone(),
+                            status: ClientBatchStatus_Status::COMMITTED,
+                            ..Default::default()
+                        })
+                        .collect();
+                    let response = ClientBatchStatusResponse {
+                        status: ClientBatchStatusResponse_Status::OK,
+                        batch_statuses: ::protobuf::RepeatedField::from_vec(statuses),
+                        ..Default::default()
+                    };
+                    (
+                        validator::Message_MessageType::CLIENT_BATCH_STATUS_RESPONSE,
+                        response.write_to_bytes().unwrap(),
+                    )
                 }
+                _ => continue,
             };
-        }
+            let reply = Message {
+                message_type,
+                correlation_id: request.get_correlation_id().to_string(),
+                content,
+                ..Default::default()
+            };
+            socket
+                .send_multipart([&parts[0][..], &reply.write_to_bytes().unwrap()[..]], 0)
+                .unwrap();
+        });
 
-        match status_response.get_status() {
-            ClientBatchStatusResponse_Status::OK => Ok(()),
-            _ => Err(SendError::UnknownError),
-        }
+        (url, batch_receiver)
+    }
+
+    #[actix_rt::test]
+    async fn dependent_transactions_are_sent_in_one_batch() {
+        let (url, batches) = fake_validator();
+        let messenger = Messenger::new(url, Metrics::default());
+        let (private_key, _) = messenger.get_new_key_pair();
+
+        let batch_id = messenger
+            .send_transactions_and_wait(&*private_key, |builder, signer| {
+                let create_id =
+                    builder.add_create_record(signer, "fish-1", 0, 0, Vec::new(), 50, Vec::new());
+                builder.add_transfer_record(signer, "fish-1", "receiver", 60, vec![create_id]);
+            })
+            .await
+            .unwrap();
+
+        let batch = batches.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(batch.get_header_signature(), batch_id);
+        let batch_header: BatchHeader = protobuf::parse_from_bytes(batch.get_header()).unwrap();
+        let transaction_ids = batch_header.get_transaction_ids();
+        assert_eq!(transaction_ids.len(), 2);
+        let transfer_header: TransactionHeader =
+            protobuf::parse_from_bytes(batch.get_transactions()[1].get_header()).unwrap();
+        assert_eq!(transfer_header.get_dependencies(), &transaction_ids[..1]);
+        assert!(batches.try_recv().is_err());
     }
 }
@@ -0,0 +1,259 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use actix_service::{Service, Transform};
+use actix_web::dev::{Path, ServiceRequest, ServiceResponse, Url};
+use actix_web::http::StatusCode;
+use actix_web::{Error, HttpRequest};
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use futures::future::{ok, Ready};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntGauge, Registry, TextEncoder,
+};
+
+/// Label for requests that matched no route, so that scanners can't create
+/// an unbounded number of series
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Prometheus metrics for the REST API, kept in their own registry
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    request_duration: HistogramVec,
+    pub batches_submitted: IntCounter,
+    pub batch_submit_failures: IntCounter,
+    pool_connections: IntGauge,
+    pool_idle_connections: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to respond to requests",
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let batches_submitted = IntCounter::new(
+            "batches_submitted_total",
+            "Batches accepted by the validator",
+        )
+        .unwrap();
+        let batch_submit_failures = IntCounter::new(
+            "batch_submit_failures_total",
+            "Batch submissions that failed or were rejected",
+        )
+        .unwrap();
+        let pool_connections = IntGauge::new(
+            "db_pool_connections",
+            "Open connections to the reporting database",
+        )
+        .unwrap();
+        let pool_idle_connections = IntGauge::new(
+            "db_pool_idle_connections",
+            "Idle connections to the reporting database",
+        )
+        .unwrap();
+
+        let registry = Registry::new();
+        registry
+            .register(Box::new(request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(batches_submitted.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(batch_submit_failures.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pool_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pool_idle_connections.clone()))
+            .unwrap();
+
+        Metrics {
+            registry,
+            request_duration,
+            batches_submitted,
+            batch_submit_failures,
+            pool_connections,
+            pool_idle_connections,
+        }
+    }
+}
+
+impl Metrics {
+    /// Renders all metrics in the Prometheus text format, returning the
+    /// content type and body
+    pub fn render(&self, pool: &Pool<ConnectionManager<PgConnection>>) -> (String, Vec<u8>) {
+        let state = pool.state();
+        self.pool_connections.set(i64::from(state.connections));
+        self.pool_idle_connections
+            .set(i64::from(state.idle_connections));
+
+        let encoder = TextEncoder::new();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("metrics are always encodable");
+        (encoder.format_type().to_string(), buffer)
+    }
+
+    fn observe_request(&self, method: &str, route: &str, status: StatusCode, started: Instant) {
+        let elapsed = started.elapsed();
+        self.request_duration
+            .with_label_values(&[method, route, status.as_str()])
+            .observe(elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9);
+    }
+}
+
+/// Rebuilds the route pattern of a request by putting the names of the
+/// matched path segments back in place of their values, e.g.
+/// `/records/{record_id}`
+fn route_pattern(segments: &Path<Url>) -> String {
+    // The values are slices of the path, so their offsets give the exact
+    // spans to replace, even where a value also appears elsewhere in it
+    let path = segments.get_ref().path();
+    let mut route = String::with_capacity(path.len());
+    let mut copied = 0;
+    for (name, value) in segments.iter() {
+        let start = value.as_ptr() as usize - path.as_ptr() as usize;
+        route.push_str(&path[copied..start]);
+        route.push_str(&format!("{{{}}}", name));
+        copied = start + value.len();
+    }
+    route.push_str(&path[copied..]);
+    route
+}
+
+/// Labels a request by the pattern of the resource which answered it. A
+/// request with no segments was either answered by a static resource or by
+/// no resource at all, which is why middleware is wrapped around resources
+/// rather than scopes: their responses would otherwise be labelled by the
+/// raw path.
+fn route_label(request: &HttpRequest, status: StatusCode) -> String {
+    let segments = request.match_info();
+    if segments.is_empty() && status == StatusCode::NOT_FOUND {
+        return UNMATCHED_ROUTE.to_string();
+    }
+    route_pattern(segments)
+}
+
+/// Middleware which times every request by method, route and status
+pub struct RequestMetrics {
+    metrics: Metrics,
+}
+
+impl RequestMetrics {
+    pub fn new(metrics: Metrics) -> Self {
+        RequestMetrics { metrics }
+    }
+}
+
+impl<S, B> Transform<S> for RequestMetrics
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestMetricsMiddleware {
+            service,
+            metrics: self.metrics.clone(),
+        })
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+    metrics: Metrics,
+}
+
+impl<S, B> Service for RequestMetricsMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        // The request can't be cloned here, since routing needs to hold the
+        // only reference to it to fill in the path segments
+        let method = req.method().clone();
+        let metrics = self.metrics.clone();
+        let response = self.service.call(req);
+
+        Box::pin(async move {
+            let response = response.await;
+            let (route, status) = match &response {
+                Ok(response) => (
+                    route_label(response.request(), response.status()),
+                    response.status(),
+                ),
+                // Errors which no handler or middleware turned into a
+                // response carry no request to label them by
+                Err(err) => (
+                    UNMATCHED_ROUTE.to_string(),
+                    err.as_response_error().status_code(),
+                ),
+            };
+            metrics.observe_request(method.as_str(), &route, status, started);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::dev::ResourceDef;
+    use actix_web::http::Uri;
+
+    fn matched(pattern: &str, path: &'static str) -> Path<Url> {
+        let mut segments = Path::new(Url::new(Uri::from_static(path)));
+        assert!(ResourceDef::new(pattern).match_path(&mut segments));
+        segments
+    }
+
+    #[test]
+    fn static_routes_keep_their_path() {
+        assert_eq!(route_pattern(&matched("/health", "/health")), "/health");
+    }
+
+    #[test]
+    fn segment_values_are_replaced_where_they_matched() {
+        assert_eq!(
+            route_pattern(&matched("/agents/{public_key}", "/agents/a")),
+            "/agents/{public_key}"
+        );
+        assert_eq!(
+            route_pattern(&matched(
+                "/records/{record_id}/transfer",
+                "/records/transfer/transfer"
+            )),
+            "/records/{record_id}/transfer"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::schema::*;
+use serde::Serialize;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
 #[table_name = "agents"]
@@ -10,7 +11,7 @@ pub struct NewAgent {
     pub end_block_num: i64,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable, Serialize)]
 pub struct Agent {
     pub id: i64,
     pub public_key: String,
@@ -32,5 +33,113 @@ pub struct NewAuth {
 pub struct Auth {
     pub public_key: String,
     pub hashed_password: String,
-    pub encrypted_private_key: String,
+    /// `None` once the agent has taken its key over from the server
+    pub encrypted_private_key: Option<String>,
+    /// Version of the tokens which are still valid, incremented whenever
+    /// the password changes
+    pub token_version: i32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
+#[table_name = "idempotency_keys"]
+pub struct NewIdempotencyKey {
+    pub idempotency_key: String,
+    pub agent: String,
+    pub request_hash: String,
+    pub created_at: i64,
+    pub claimed_at: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable)]
+pub struct IdempotencyKey {
+    pub idempotency_key: String,
+    pub agent: String,
+    pub request_hash: String,
+    pub status_code: Option<i32>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: i64,
+    pub claimed_at: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
+#[table_name = "blocks"]
+pub struct NewBlock {
+    pub block_num: i64,
+    pub block_id: String,
+    /// The latest transaction timestamp of this block or any before it, as
+    /// a Unix UTC timestamp. None until a block carries any transactions.
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
+#[table_name = "records"]
+pub struct NewRecord {
+    pub record_id: String,
+    pub assembly: Option<String>,
+    pub in_violation: bool,
+    // The latest owner and location, and the time of the latest change to
+    // either, kept on the record so lists can be filtered on them
+    pub owner: Option<String>,
+    pub latitude: Option<i64>,
+    pub longitude: Option<i64>,
+    pub last_updated: Option<i64>,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable, Serialize)]
+pub struct Record {
+    pub id: i64,
+    pub record_id: String,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+    pub assembly: Option<String>,
+    pub in_violation: bool,
+    pub owner: Option<String>,
+    pub latitude: Option<i64>,
+    pub longitude: Option<i64>,
+    pub last_updated: Option<i64>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
+#[table_name = "record_owners"]
+pub struct NewRecordOwner {
+    pub record_id: String,
+    pub agent_id: String,
+    pub timestamp: i64,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable, Serialize)]
+pub struct RecordOwner {
+    pub id: i64,
+    pub record_id: String,
+    pub agent_id: String,
+    pub timestamp: i64,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Insertable)]
+#[table_name = "record_locations"]
+pub struct NewRecordLocation {
+    pub record_id: String,
+    pub latitude: i64,
+    pub longitude: i64,
+    pub timestamp: i64,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Queryable, Serialize)]
+pub struct RecordLocation {
+    pub id: i64,
+    pub record_id: String,
+    pub latitude: i64,
+    pub longitude: i64,
+    pub timestamp: i64,
+    pub start_block_num: i64,
+    pub end_block_num: i64,
 }
@@ -0,0 +1,542 @@
+use std::cell::RefCell;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{ObjectValidation, Schema};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+use crate::error::ErrorResponse;
+use crate::events::{EventFilter, RecordEvent};
+use crate::geojson::{Feature, FeatureCollection};
+use crate::handler::*;
+use crate::idempotency::IDEMPOTENCY_KEY;
+
+const JSON: &str = "application/json";
+
+/// Builds the OpenAPI 3 document describing the routes registered in `app`,
+/// with schemas generated from the request and response types
+pub fn document() -> Value {
+    let mut api = OpenApi::new();
+
+    api.add(
+        "get",
+        "/agents",
+        Operation::new("List agents")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .query(query_parameters::<AgentFilterQuery>())
+            .query(query_parameters::<PagingQuery>())
+            .ok(JSON, api.schema::<ListResponse<AgentResponse>>()),
+    );
+    api.add(
+        "post",
+        "/agents",
+        Operation::new("Create an agent, whose key is held by the server")
+            .idempotent()
+            .body(JSON, api.schema::<CreateAgentRequest>())
+            .response(201, JSON, api.schema::<CreateAgentResponse>()),
+    );
+    api.add(
+        "get",
+        "/agents/{public_key}",
+        Operation::new("Fetch an agent")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .ok(JSON, api.schema::<AgentResponse>()),
+    );
+    api.add(
+        "post",
+        "/authentication",
+        Operation::new("Exchange a public key and password for a token")
+            .body(JSON, api.schema::<AuthenticationRequest>())
+            .ok(JSON, api.schema::<AuthenticationResponse>()),
+    );
+    api.add(
+        "post",
+        "/authentication/password",
+        Operation::new("Change the password of the authenticated agent, revoking its tokens")
+            .authenticated()
+            .body(JSON, api.schema::<ChangePasswordRequest>())
+            .ok(JSON, api.schema::<AuthenticationResponse>()),
+    );
+    api.add(
+        "post",
+        "/authentication/private_key",
+        Operation::new("Export the private key of the authenticated agent")
+            .authenticated()
+            .body(JSON, api.schema::<ExportKeyRequest>())
+            .ok(JSON, api.schema::<ExportKeyResponse>()),
+    );
+    api.add(
+        "delete",
+        "/authentication/private_key",
+        Operation::new("Delete the server's copy of the authenticated agent's private key")
+            .authenticated()
+            .body(JSON, api.schema::<DeleteKeyRequest>())
+            .empty(204),
+    );
+    api.add(
+        "post",
+        "/batches",
+        Operation::new("Submit batches signed by the client")
+            .idempotent()
+            .body(
+                "application/octet-stream",
+                json!({ "type": "string", "format": "binary" }),
+            )
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "get",
+        "/batch_statuses",
+        Operation::new("Look up the status of submitted batches")
+            .query(query_parameters::<BatchStatusQuery>())
+            .ok(JSON, api.schema::<Vec<BatchStatusResponse>>()),
+    );
+    api.add(
+        "get",
+        "/events",
+        Operation::new("Stream record changes as server-sent events")
+            .authenticated()
+            .query(query_parameters::<EventFilter>())
+            .ok("text/event-stream", api.schema::<RecordEvent>()),
+    );
+    api.add(
+        "get",
+        "/export/{table}",
+        Operation::new("Export agents, records, owners or locations")
+            .authenticated()
+            .query(query_parameters::<ExportQuery>())
+            .ok("text/csv", json!({ "type": "string" }))
+            .ok("application/x-ndjson", json!({ "type": "string" })),
+    );
+    api.add(
+        "get",
+        "/records",
+        Operation::new("List records")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .query(query_parameters::<RecordFilterQuery>())
+            .query(query_parameters::<PagingQuery>())
+            .ok(JSON, api.schema::<ListResponse<RecordResponse>>()),
+    );
+    api.add(
+        "post",
+        "/records",
+        Operation::new("Create a record")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .body(JSON, api.schema::<CreateRecordRequest>())
+            .response(201, JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "get",
+        "/records.geojson",
+        Operation::new("Current positions of records")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .query(query_parameters::<RecordFilterQuery>())
+            .query(query_parameters::<PagingQuery>())
+            .ok(
+                crate::geojson::CONTENT_TYPE,
+                api.schema::<FeatureCollection<PositionsMetadata>>(),
+            ),
+    );
+    api.add(
+        "get",
+        "/records/{record_id}",
+        Operation::new("Fetch a record")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .ok(JSON, api.schema::<RecordResponse>()),
+    );
+    api.add(
+        "patch",
+        "/records/{record_id}",
+        Operation::new("Report the location and properties of a record")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .body(JSON, api.schema::<UpdateRecordRequest>())
+            .ok(JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "post",
+        "/records/{record_id}/transfer",
+        Operation::new("Transfer a record to another agent")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .body(JSON, api.schema::<TransferRecordRequest>())
+            .ok(JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "post",
+        "/records/{record_id}/assemble",
+        Operation::new("Assemble records owned by the agent into a record")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .body(JSON, api.schema::<ComponentsRequest>())
+            .ok(JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "post",
+        "/records/{record_id}/disassemble",
+        Operation::new("Take components out of an assembled record")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .body(JSON, api.schema::<ComponentsRequest>())
+            .ok(JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "post",
+        "/records/{record_id}/recall",
+        Operation::new("Recall a record, which only the agent that created it can do")
+            .authenticated()
+            .idempotent()
+            .query(query_parameters::<SubmitOptions>())
+            .ok(JSON, api.schema::<RecordResponse>())
+            .response(202, JSON, api.schema::<SubmitBatchesResponse>()),
+    );
+    api.add(
+        "get",
+        "/records/{record_id}/track.geojson",
+        Operation::new("The locations of a record as a track")
+            .authenticated()
+            .query(query_parameters::<StateQuery>())
+            .ok(crate::geojson::CONTENT_TYPE, api.schema::<Feature>()),
+    );
+    api.add(
+        "get",
+        "/health",
+        Operation::new("Liveness check").ok(JSON, json!({ "type": "object" })),
+    );
+    api.add(
+        "get",
+        "/ready",
+        Operation::new("Readiness check")
+            .ok(JSON, api.schema::<ReadinessResponse>())
+            .response(503, JSON, api.schema::<ReadinessResponse>()),
+    );
+    api.add(
+        "get",
+        "/metrics",
+        Operation::new("Metrics in the Prometheus text format")
+            .ok("text/plain", json!({ "type": "string" })),
+    );
+    api.add(
+        "get",
+        "/openapi.json",
+        Operation::new("This document").ok(JSON, json!({ "type": "object" })),
+    );
+
+    api.into_document()
+}
+
+struct OpenApi {
+    // Shared so that schemas can be looked up while an operation is added
+    generator: RefCell<SchemaGenerator>,
+    paths: Map<String, Value>,
+    error: Value,
+}
+
+impl OpenApi {
+    fn new() -> Self {
+        let mut generator = SchemaSettings::openapi3().into_generator();
+        let error = to_value(generator.subschema_for::<ErrorResponse>());
+        OpenApi {
+            generator: RefCell::new(generator),
+            paths: Map::new(),
+            error,
+        }
+    }
+
+    /// A reference to the schema of `T`, which is added to the components
+    fn schema<T: JsonSchema>(&self) -> Value {
+        to_value(self.generator.borrow_mut().subschema_for::<T>())
+    }
+
+    fn add(&mut self, method: &str, path: &str, operation: Operation) {
+        let mut operation = operation.0;
+        let mut parameters: Vec<Value> = path
+            .split('/')
+            .filter(|segment| segment.starts_with('{') && segment.ends_with('}'))
+            .map(|segment| {
+                json!({
+                    "name": &segment[1..segment.len() - 1],
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        if let Some(Value::Array(query)) = operation.remove("parameters") {
+            parameters.extend(query);
+        }
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_string(), Value::Array(parameters));
+        }
+        operation["responses"]["default"] = json!({
+            "description": "Error",
+            "content": { JSON: { "schema": self.error } },
+        });
+
+        self.paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()))[method] = Value::Object(operation);
+    }
+
+    fn into_document(self) -> Value {
+        let schemas: Map<String, Value> = self
+            .generator
+            .into_inner()
+            .take_definitions()
+            .into_iter()
+            .map(|(name, schema)| (name, to_value(schema)))
+            .collect();
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Simple Supply REST API",
+                "description": "Agents, records and their history can only be read with a \
+                                token from `POST /authentication`. Creating an agent, \
+                                submitting batches signed by the client, looking up their \
+                                status and the health, metrics and document endpoints \
+                                need none. A password can be changed, which revokes the \
+                                tokens issued before, but not reset, since the server \
+                                has no other way to tell who is asking.",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": self.paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+                },
+            },
+        })
+    }
+}
+
+struct Operation(Map<String, Value>);
+
+impl Operation {
+    fn new(summary: &str) -> Self {
+        let mut operation = Map::new();
+        operation.insert("summary".to_string(), json!(summary));
+        operation.insert("responses".to_string(), json!({}));
+        Operation(operation)
+    }
+
+    fn authenticated(mut self) -> Self {
+        self.0
+            .insert("security".to_string(), json!([{ "bearerAuth": [] }]));
+        self
+    }
+
+    /// Documents the header which makes retries of a write safe
+    fn idempotent(self) -> Self {
+        self.query(vec![json!({
+            "name": IDEMPOTENCY_KEY,
+            "in": "header",
+            "description": "Unique value identifying the request. Retrying with the same \
+                            key returns the first response instead of submitting again.",
+            "schema": { "type": "string", "maxLength": 255 },
+        })])
+    }
+
+    fn query(mut self, parameters: Vec<Value>) -> Self {
+        let existing = self
+            .0
+            .entry("parameters")
+            .or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(existing) = existing {
+            existing.extend(parameters);
+        }
+        self
+    }
+
+    fn body(mut self, content_type: &str, schema: Value) -> Self {
+        self.0.insert(
+            "requestBody".to_string(),
+            json!({ "required": true, "content": { content_type: { "schema": schema } } }),
+        );
+        self
+    }
+
+    fn ok(self, content_type: &str, schema: Value) -> Self {
+        self.response(200, content_type, schema)
+    }
+
+    /// Adds a response, or another content type for a status already added
+    fn response(mut self, status: u16, content_type: &str, schema: Value) -> Self {
+        let response = self.0["responses"]
+            .as_object_mut()
+            .unwrap()
+            .entry(status.to_string())
+            .or_insert_with(|| json!({ "description": status_description(status), "content": {} }));
+        response["content"][content_type] = json!({ "schema": schema });
+        self
+    }
+
+    /// Adds a response without a body
+    fn empty(mut self, status: u16) -> Self {
+        self.0["responses"][status.to_string()] =
+            json!({ "description": status_description(status) });
+        self
+    }
+}
+
+fn status_description(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted, but not committed yet",
+        204 => "No content",
+        503 => "Service unavailable",
+        _ => "Response",
+    }
+}
+
+/// Describes each field of a query string type as a parameter
+fn query_parameters<T: JsonSchema>() -> Vec<Value> {
+    let mut settings = SchemaSettings::openapi3();
+    settings.inline_subschemas = true;
+    let root = settings.into_generator().into_root_schema_for::<T>();
+    let ObjectValidation {
+        properties,
+        required,
+        ..
+    } = match root.schema.object {
+        Some(object) => *object,
+        None => return vec![],
+    };
+    properties
+        .into_iter()
+        .map(|(name, schema)| {
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "required": required.contains(&name),
+                "schema": to_value(schema),
+            });
+            if let Some(description) = parameter["schema"].get("description").cloned() {
+                parameter["description"] = description;
+            }
+            parameter
+        })
+        .collect()
+}
+
+fn to_value(schema: Schema) -> Value {
+    serde_json::to_value(schema).expect("schemas always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::{Method, StatusCode};
+    use actix_web::{test, web, App};
+    use diesel::pg::PgConnection;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use futures::future::ok;
+
+    use crate::authentication::{generate_auth_token, Authentication};
+    use crate::handler::not_found;
+    use crate::idempotency::Idempotency;
+    use crate::rate_limit::{RateLimiter, RateLimits};
+    use crate::routes;
+
+    const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+    const SECRET: &str = "openapi-test";
+
+    /// Requests every method on every documented path of an app without any
+    /// data, so that each handler fails before doing any work. Anything but
+    /// the fallback's 404 or a 405 means a route exists.
+    #[actix_rt::test]
+    async fn document_matches_routes() {
+        // Never connects, since no request carries an idempotency key
+        let pool = Pool::builder().build_unchecked(ConnectionManager::<PgConnection>::new(
+            "postgres://localhost",
+        ));
+        let mut app = test::init_service(
+            App::new()
+                .default_service(web::route().to(not_found))
+                .configure(|cfg| {
+                    routes(
+                        cfg,
+                        Authentication::new(SECRET, |_| ok(Some(0))),
+                        Idempotency::new(pool, 1024, SECRET.as_bytes()),
+                        RateLimiter::new(RateLimits::default()),
+                    )
+                }),
+        )
+        .await;
+        let token = generate_auth_token(SECRET, "openapi-test-agent", 0).unwrap();
+        let document = document();
+
+        for (path, operations) in document["paths"].as_object().unwrap() {
+            let uri = path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        "test"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+
+            for method in METHODS.iter() {
+                let http_method = Method::from_bytes(method.to_uppercase().as_bytes()).unwrap();
+                let request = test::TestRequest::default()
+                    .method(http_method.clone())
+                    .uri(&uri)
+                    .header(AUTHORIZATION, format!("Bearer {}", token))
+                    .to_request();
+                let status = test::call_service(&mut app, request).await.status();
+                let routed =
+                    status != StatusCode::NOT_FOUND && status != StatusCode::METHOD_NOT_ALLOWED;
+                let operation = operations.get(*method);
+                assert_eq!(
+                    routed,
+                    operation.is_some(),
+                    "{} {} is {}",
+                    method,
+                    path,
+                    if routed {
+                        "not documented"
+                    } else {
+                        "not routed"
+                    }
+                );
+
+                if let Some(operation) = operation {
+                    let request = test::TestRequest::default()
+                        .method(http_method)
+                        .uri(&uri)
+                        .to_request();
+                    let status = test::call_service(&mut app, request).await.status();
+                    assert_eq!(
+                        status == StatusCode::UNAUTHORIZED,
+                        operation.get("security").is_some(),
+                        "authentication of {} {} is documented wrongly",
+                        method,
+                        path
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::{Error, HttpMessage};
+use futures::future::{ok, Ready};
+use serde::Deserialize;
+
+use crate::authentication::AuthenticatedAgent;
+use crate::error::ApiError;
+
+/// Most buckets kept at once
+const MAX_BUCKETS: usize = 10_000;
+/// Buckets are split between this many maps, each behind its own lock, so
+/// that requests rarely wait on each other and making room in one only holds
+/// up a small part of the clients
+const SHARDS: usize = 16;
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// How often a client may call a route: `burst` requests at once, refilled
+/// at `requests_per_minute`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Limit {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+}
+
+/// The limit for each route, keyed as `METHOD /pattern`, e.g.
+/// `POST /records/{record_id}/transfer`
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    pub default: Limit,
+    /// The limit of each address on an authenticated route, checked before
+    /// its token. It is looser than the others, since several agents may
+    /// share an address.
+    pub address: Limit,
+    routes: HashMap<String, Limit>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        // Routes which hash passwords or create keys are far more expensive
+        // than the rest, and the password ones invite guessing
+        let strict = Limit {
+            requests_per_minute: 10,
+            burst: 5,
+        };
+        let routes = [
+            "POST /agents",
+            "POST /authentication",
+            "POST /authentication/password",
+            "POST /authentication/private_key",
+            "DELETE /authentication/private_key",
+        ]
+        .iter()
+        .map(|route| (route.to_string(), strict))
+        .collect();
+
+        RateLimits {
+            default: Limit {
+                requests_per_minute: 120,
+                burst: 30,
+            },
+            address: Limit {
+                requests_per_minute: 600,
+                burst: 120,
+            },
+            routes,
+        }
+    }
+}
+
+impl RateLimits {
+    /// Sets the limit of one route, checking that it is written as
+    /// `METHOD /pattern`
+    pub fn set_route(&mut self, route: &str, limit: Limit) -> Result<(), String> {
+        let mut parts = route.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(path), None)
+                if METHODS.contains(&method.to_uppercase().as_str()) && path.starts_with('/') =>
+            {
+                self.routes
+                    .insert(format!("{} {}", method.to_uppercase(), path), limit);
+                Ok(())
+            }
+            _ => Err(format!(
+                "{} is not a method and path such as \"POST /agents\"",
+                route
+            )),
+        }
+    }
+
+    /// Checks that no limit blocks its route entirely
+    pub fn validate(&self) -> Result<(), String> {
+        let zero = |limit: &Limit| limit.requests_per_minute == 0 || limit.burst == 0;
+        if zero(&self.default) {
+            return Err("the default must be above zero".to_string());
+        }
+        if zero(&self.address) {
+            return Err("the address limit must be above zero".to_string());
+        }
+        match self.routes.iter().find(|(_, limit)| zero(limit)) {
+            Some((route, _)) => Err(format!("{} must be above zero", route)),
+            None => Ok(()),
+        }
+    }
+
+    fn limit_for(&self, route: &str) -> Limit {
+        self.routes.get(route).copied().unwrap_or(self.default)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// When the bucket will be full again, after which it can be dropped
+    full_at: Instant,
+}
+
+type Buckets = HashMap<(String, String), Bucket>;
+
+/// Token buckets of every client and route, shared by all workers
+#[derive(Clone)]
+pub struct RateLimiter {
+    limits: Arc<RateLimits>,
+    shards: Arc<Vec<Mutex<Buckets>>>,
+    // Seeded randomly, so that clients can't pick which shard they land in
+    hasher: RandomState,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        RateLimiter {
+            limits: Arc::new(limits),
+            shards: Arc::new((0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect()),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Takes a token from the bucket of `client` for `route`, or returns how
+    /// long until one is available
+    fn take(&self, client: String, route: &str, now: Instant) -> Result<(), Duration> {
+        self.take_limited(client, route, self.limits.limit_for(route), now)
+    }
+
+    /// Like `take`, but under the limit for addresses on authenticated routes
+    fn take_for_address(&self, client: String, route: &str, now: Instant) -> Result<(), Duration> {
+        self.take_limited(client, route, self.limits.address, now)
+    }
+
+    fn take_limited(
+        &self,
+        client: String,
+        route: &str,
+        limit: Limit,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let burst = f64::from(limit.burst);
+        let per_second = f64::from(limit.requests_per_minute) / 60.0;
+
+        let key = (client, route.to_string());
+        let shard = &self.shards[self.hasher.hash_one(&key) as usize % SHARDS];
+        let mut buckets = shard.lock().expect("rate limit buckets poisoned");
+        if buckets.len() >= MAX_BUCKETS / SHARDS && !buckets.contains_key(&key) {
+            make_room(&mut buckets, now);
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+            full_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated);
+        bucket.tokens = (bucket.tokens + duration_secs(elapsed) * per_second).min(burst);
+        bucket.updated = now;
+        let result = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        };
+        bucket.full_at = now + Duration::from_secs_f64((burst - bucket.tokens) / per_second);
+        result
+    }
+}
+
+/// Drops the buckets which have filled up again, since a new one would be
+/// the same, or failing that the one closest to full
+fn make_room(buckets: &mut Buckets, now: Instant) {
+    buckets.retain(|_, bucket| bucket.full_at > now);
+    if buckets.len() < MAX_BUCKETS / SHARDS {
+        return;
+    }
+    let fullest = buckets
+        .iter()
+        .min_by_key(|(_, bucket)| bucket.full_at)
+        .map(|(key, _)| key.clone());
+    if let Some(key) = fullest {
+        buckets.remove(&key);
+    }
+}
+
+fn duration_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
+
+/// Middleware which limits how often each client calls a route. Clients are
+/// told apart by their agent where the route is authenticated, so it has to
+/// run inside `Authentication` there, and by address otherwise.
+///
+/// Authenticated routes also get a limit by address, which runs before
+/// `Authentication` so that requests with bad tokens are held back too.
+#[derive(Clone)]
+pub struct RateLimit {
+    limiter: RateLimiter,
+    route: &'static str,
+    by_address: bool,
+}
+
+impl RateLimit {
+    /// Limits the resource registered under the pattern `route`, which the
+    /// limits of `RateLimits` are looked up by together with the method
+    pub fn new(limiter: RateLimiter, route: &'static str) -> Self {
+        RateLimit {
+            limiter,
+            route,
+            by_address: false,
+        }
+    }
+
+    /// Limits each address on the authenticated resource registered under
+    /// `route`, by the address limit of `RateLimits`
+    pub fn by_address(limiter: RateLimiter, route: &'static str) -> Self {
+        RateLimit {
+            limiter,
+            route,
+            by_address: true,
+        }
+    }
+}
+
+impl<S, B> Transform<S> for RateLimit
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RateLimitMiddleware {
+            service,
+            limiter: self.limiter.clone(),
+            route: self.route,
+            by_address: self.by_address,
+        })
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: S,
+    limiter: RateLimiter,
+    route: &'static str,
+    by_address: bool,
+}
+
+impl<S, B> Service for RateLimitMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let route = format!("{} {}", req.method(), self.route);
+        let taken = if self.by_address {
+            self.limiter
+                .take_for_address(address(&req), &route, Instant::now())
+        } else {
+            let client = match req.extensions().get::<AuthenticatedAgent>() {
+                Some(agent) => format!("agent {}", agent.public_key),
+                None => address(&req),
+            };
+            self.limiter.take(client, &route, Instant::now())
+        };
+
+        match taken {
+            Ok(()) => Box::pin(self.service.call(req)),
+            Err(wait) => {
+                let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+                Box::pin(ok(
+                    req.error_response(ApiError::TooManyRequests(retry_after))
+                ))
+            }
+        }
+    }
+}
+
+fn address(req: &ServiceRequest) -> String {
+    match req.head().peer_addr {
+        Some(addr) => format!("address {}", addr.ip()),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_minute: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimits {
+            default: Limit {
+                requests_per_minute,
+                burst,
+            },
+            ..RateLimits::default()
+        })
+    }
+
+    fn bucket_count(limiter: &RateLimiter) -> usize {
+        limiter
+            .shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    #[test]
+    fn bursts_are_allowed_then_refilled() {
+        let limiter = limiter(60, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(
+                limiter.take("client".to_string(), "GET /records", now),
+                Ok(())
+            );
+        }
+        let wait = limiter
+            .take("client".to_string(), "GET /records", now)
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            limiter.take("client".to_string(), "GET /records", later),
+            Ok(())
+        );
+        assert!(limiter
+            .take("client".to_string(), "GET /records", later)
+            .is_err());
+    }
+
+    #[test]
+    fn clients_and_routes_have_their_own_buckets() {
+        let limiter = limiter(60, 1);
+        let now = Instant::now();
+        assert_eq!(
+            limiter.take("client".to_string(), "GET /records", now),
+            Ok(())
+        );
+        assert!(limiter
+            .take("client".to_string(), "GET /records", now)
+            .is_err());
+        assert_eq!(
+            limiter.take("other".to_string(), "GET /records", now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.take("client".to_string(), "POST /records", now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn routes_use_their_own_limit() {
+        let limiter = limiter(60, 10);
+        let now = Instant::now();
+        // Five at once, as set for logging in by default
+        for _ in 0..5 {
+            assert_eq!(
+                limiter.take("client".to_string(), "POST /authentication", now),
+                Ok(())
+            );
+        }
+        assert!(limiter
+            .take("client".to_string(), "POST /authentication", now)
+            .is_err());
+    }
+
+    #[test]
+    fn addresses_use_their_own_limit() {
+        let limiter = RateLimiter::new(RateLimits {
+            address: Limit {
+                requests_per_minute: 60,
+                burst: 2,
+            },
+            ..RateLimits::default()
+        });
+        let now = Instant::now();
+        for _ in 0..2 {
+            assert_eq!(
+                limiter.take_for_address("address".to_string(), "GET /records", now),
+                Ok(())
+            );
+        }
+        assert!(limiter
+            .take_for_address("address".to_string(), "GET /records", now)
+            .is_err());
+        // Agents are limited apart from the address they call from
+        assert_eq!(
+            limiter.take("agent".to_string(), "GET /records", now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn buckets_are_capped() {
+        let limiter = limiter(1, 1);
+        let now = Instant::now();
+        for client in 0..MAX_BUCKETS * 2 {
+            let _ = limiter.take(client.to_string(), "GET /records", now);
+        }
+        assert!(bucket_count(&limiter) <= MAX_BUCKETS);
+
+        // Buckets which have filled up again are the first to go
+        let later = now + Duration::from_secs(60);
+        let _ = limiter.take("new client".to_string(), "GET /records", later);
+        assert!(bucket_count(&limiter) < MAX_BUCKETS);
+    }
+
+    #[test]
+    fn routes_are_a_method_and_a_path() {
+        let limit = Limit {
+            requests_per_minute: 1,
+            burst: 1,
+        };
+        let mut limits = RateLimits::default();
+        assert!(limits.set_route("post /batches", limit).is_ok());
+        assert_eq!(limits.limit_for("POST /batches"), limit);
+
+        assert!(limits.set_route("/batches", limit).is_err());
+        assert!(limits.set_route("FETCH /batches", limit).is_err());
+        assert!(limits.set_route("POST batches", limit).is_err());
+        assert!(limits.set_route("POST /batches extra", limit).is_err());
+    }
+
+    #[test]
+    fn limits_must_be_above_zero() {
+        assert!(RateLimits::default().validate().is_ok());
+
+        let mut limits = RateLimits::default();
+        limits.default.burst = 0;
+        assert!(limits.validate().is_err());
+
+        let mut limits = RateLimits::default();
+        limits.address.requests_per_minute = 0;
+        assert!(limits.validate().is_err());
+
+        let mut limits = RateLimits::default();
+        let zero = Limit {
+            requests_per_minute: 0,
+            burst: 1,
+        };
+        limits.set_route("POST /batches", zero).unwrap();
+        assert_eq!(
+            limits.validate(),
+            Err("POST /batches must be above zero".to_string())
+        );
+    }
+}
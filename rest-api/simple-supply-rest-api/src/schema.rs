@@ -1,19 +1,20 @@
 table! {
     agents (id) {
         id -> Int8,
-        public_key -> Nullable<Varchar>,
-        name -> Nullable<Varchar>,
-        timestamp -> Nullable<Int8>,
-        start_block_num -> Nullable<Int8>,
-        end_block_num -> Nullable<Int8>,
+        public_key -> Varchar,
+        name -> Varchar,
+        timestamp -> Int8,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
     }
 }
 
 table! {
     auths (public_key) {
         public_key -> Varchar,
-        hashed_password -> Nullable<Varchar>,
+        hashed_password -> Varchar,
         encrypted_private_key -> Nullable<Varchar>,
+        token_version -> Int4,
     }
 }
 
@@ -21,38 +22,69 @@ table! {
     blocks (block_num) {
         block_num -> Int8,
         block_id -> Nullable<Varchar>,
+        timestamp -> Nullable<Int8>,
+    }
+}
+
+table! {
+    idempotency_keys (idempotency_key, agent) {
+        idempotency_key -> Varchar,
+        agent -> Varchar,
+        request_hash -> Varchar,
+        status_code -> Nullable<Int4>,
+        content_type -> Nullable<Varchar>,
+        response_body -> Nullable<Bytea>,
+        created_at -> Int8,
+        claimed_at -> Int8,
     }
 }
 
 table! {
     record_locations (id) {
         id -> Int8,
-        record_id -> Nullable<Varchar>,
-        latitude -> Nullable<Int8>,
-        longitude -> Nullable<Int8>,
-        timestamp -> Nullable<Int8>,
-        start_block_num -> Nullable<Int8>,
-        end_block_num -> Nullable<Int8>,
+        record_id -> Varchar,
+        latitude -> Int8,
+        longitude -> Int8,
+        timestamp -> Int8,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
     }
 }
 
 table! {
     record_owners (id) {
         id -> Int8,
-        record_id -> Nullable<Varchar>,
-        agent_id -> Nullable<Varchar>,
-        timestamp -> Nullable<Int8>,
-        start_block_num -> Nullable<Int8>,
-        end_block_num -> Nullable<Int8>,
+        record_id -> Varchar,
+        agent_id -> Varchar,
+        timestamp -> Int8,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
+    }
+}
+
+table! {
+    record_recalls (id) {
+        id -> Int8,
+        record_id -> Varchar,
+        source_record_id -> Varchar,
+        timestamp -> Int8,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
     }
 }
 
 table! {
     records (id) {
         id -> Int8,
-        record_id -> Nullable<Varchar>,
-        start_block_num -> Nullable<Int8>,
-        end_block_num -> Nullable<Int8>,
+        record_id -> Varchar,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
+        assembly -> Nullable<Varchar>,
+        in_violation -> Bool,
+        owner -> Nullable<Varchar>,
+        latitude -> Nullable<Int8>,
+        longitude -> Nullable<Int8>,
+        last_updated -> Nullable<Int8>,
     }
 }
 
@@ -60,7 +92,9 @@ allow_tables_to_appear_in_same_query!(
     agents,
     auths,
     blocks,
+    idempotency_keys,
     record_locations,
     record_owners,
+    record_recalls,
     records,
 );
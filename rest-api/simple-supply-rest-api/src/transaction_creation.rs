@@ -1,11 +1,16 @@
 use crypto::digest::Digest;
 use crypto::sha2::Sha512;
 use protobuf::Message;
-use protos::payload::{CreateAgentAction, SimpleSupplyPayload, SimpleSupplyPayload_Action};
+use protos::payload::{
+    AssembleRecordAction, CreateAgentAction, CreateRecordAction, DisassembleRecordAction,
+    PropertyBound, PropertyValue, RecallRecordAction, SimpleSupplyPayload,
+    SimpleSupplyPayload_Action, TransferRecordAction, UpdateRecordAction,
+};
 use sawtooth_sdk::messages::batch::{Batch, BatchHeader};
 use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
 use sawtooth_sdk::signing::Signer;
 use simple_supply_tp::addresser;
+use uuid::Uuid;
 
 pub fn make_create_agent_transaction(
     transaction_signer: Signer,
@@ -13,77 +18,481 @@ pub fn make_create_agent_transaction(
     name: &str,
     timestamp: i64,
 ) -> Batch {
-    let public_key = transaction_signer.get_public_key().unwrap();
-    let agent_address = addresser::get_agent_address(&public_key.as_hex());
-    let create_agent = CreateAgentAction {
-        name: name.to_string(),
-        ..Default::default()
-    };
-
-    let inputs = vec![agent_address.clone()];
-
-    let outputs = vec![agent_address.clone()];
-
-    let payload = SimpleSupplyPayload {
-        action: SimpleSupplyPayload_Action::CREATE_AGENT,
-        create_agent: ::protobuf::SingularPtrField::some(create_agent),
-        timestamp: timestamp as u64,
-        ..Default::default()
-    };
-    let payload_bytes = payload.write_to_bytes().unwrap();
-
-    make_batch(
-        payload_bytes,
-        inputs,
-        outputs,
-        transaction_signer,
-        batch_signer,
-    )
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_create_agent(&transaction_signer, name, timestamp, Vec::new());
+    builder.build()
 }
 
-fn make_batch(
-    payload_bytes: Vec<u8>,
-    inputs: Vec<String>,
-    outputs: Vec<String>,
+pub fn make_create_record_transaction(
     transaction_signer: Signer,
     batch_signer: Signer,
+    record_id: &str,
+    latitude: i64,
+    longitude: i64,
+    bounds: Vec<PropertyBound>,
+    timestamp: i64,
+) -> Batch {
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_create_record(
+        &transaction_signer,
+        record_id,
+        latitude,
+        longitude,
+        bounds,
+        timestamp,
+        Vec::new(),
+    );
+    builder.build()
+}
+
+pub fn make_update_record_transaction(
+    transaction_signer: Signer,
+    batch_signer: Signer,
+    record_id: &str,
+    latitude: i64,
+    longitude: i64,
+    properties: Vec<PropertyValue>,
+    timestamp: i64,
+) -> Batch {
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_update_record(
+        &transaction_signer,
+        record_id,
+        latitude,
+        longitude,
+        properties,
+        timestamp,
+        Vec::new(),
+    );
+    builder.build()
+}
+
+pub fn make_transfer_record_transaction(
+    transaction_signer: Signer,
+    batch_signer: Signer,
+    record_id: &str,
+    receiving_agent: &str,
+    timestamp: i64,
+) -> Batch {
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_transfer_record(
+        &transaction_signer,
+        record_id,
+        receiving_agent,
+        timestamp,
+        Vec::new(),
+    );
+    builder.build()
+}
+
+pub fn make_assemble_record_transaction(
+    transaction_signer: Signer,
+    batch_signer: Signer,
+    record_id: &str,
+    component_ids: &[String],
+    timestamp: i64,
+) -> Batch {
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_assemble_record(
+        &transaction_signer,
+        record_id,
+        component_ids,
+        timestamp,
+        Vec::new(),
+    );
+    builder.build()
+}
+
+pub fn make_disassemble_record_transaction(
+    transaction_signer: Signer,
+    batch_signer: Signer,
+    record_id: &str,
+    component_ids: &[String],
+    timestamp: i64,
+) -> Batch {
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_disassemble_record(
+        &transaction_signer,
+        record_id,
+        component_ids,
+        timestamp,
+        Vec::new(),
+    );
+    builder.build()
+}
+
+pub fn make_recall_record_transaction(
+    transaction_signer: Signer,
+    batch_signer: Signer,
+    record_id: &str,
+    timestamp: i64,
 ) -> Batch {
-    let transaction_header = TransactionHeader {
-        family_name: addresser::FAMILY_NAME.to_string(),
-        family_version: addresser::FAMILY_VERSION.to_string(),
-        inputs: ::protobuf::RepeatedField::from_vec(inputs),
-        outputs: ::protobuf::RepeatedField::from_vec(outputs),
-        signer_public_key: transaction_signer.get_public_key().unwrap().as_hex(),
-        batcher_public_key: batch_signer.get_public_key().unwrap().as_hex(),
-        dependencies: ::protobuf::RepeatedField::new(),
-        payload_sha512: sha512(&payload_bytes),
-        ..Default::default()
-    };
-
-    let transaction_header_bytes = transaction_header.write_to_bytes().unwrap();
-
-    let transaction = Transaction {
-        header: transaction_header_bytes.clone(),
-        header_signature: transaction_signer.sign(&transaction_header_bytes).unwrap(),
-        payload: payload_bytes,
-        ..Default::default()
-    };
-
-    let batch_header = BatchHeader {
-        signer_public_key: batch_signer.get_public_key().unwrap().as_hex(),
-        transaction_ids: ::protobuf::RepeatedField::from_vec(vec![transaction
-            .header_signature
-            .clone()]),
-        ..Default::default()
-    };
-
-    let batch_header_bytes = batch_header.write_to_bytes().unwrap();
-
-    Batch {
-        header: batch_header_bytes.clone(),
-        header_signature: batch_signer.sign(&batch_header_bytes).unwrap(),
-        transactions: ::protobuf::RepeatedField::from_vec(vec![transaction]),
-        ..Default::default()
+    let mut builder = BatchBuilder::new(batch_signer);
+    builder.add_recall_record(&transaction_signer, record_id, timestamp, Vec::new());
+    builder.build()
+}
+
+/// Collects transactions, which may be signed by different signers, into a
+/// single batch. The validator commits all of them or none of them.
+pub struct BatchBuilder<'a> {
+    batch_signer: Signer<'a>,
+    transactions: Vec<Transaction>,
+}
+
+impl<'a> BatchBuilder<'a> {
+    pub fn new(batch_signer: Signer<'a>) -> BatchBuilder<'a> {
+        BatchBuilder {
+            batch_signer,
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Signs and appends a transaction, returning its id so that later
+    /// transactions can list it in their `dependencies`. The typed helpers
+    /// below build the payload, inputs and outputs for each action and take
+    /// the dependencies as their last argument.
+    pub fn add_transaction(
+        &mut self,
+        transaction_signer: &Signer,
+        payload_bytes: Vec<u8>,
+        inputs: Vec<String>,
+        outputs: Vec<String>,
+        dependencies: Vec<String>,
+    ) -> String {
+        let transaction_header = TransactionHeader {
+            family_name: addresser::FAMILY_NAME.to_string(),
+            family_version: addresser::FAMILY_VERSION.to_string(),
+            inputs: ::protobuf::RepeatedField::from_vec(inputs),
+            outputs: ::protobuf::RepeatedField::from_vec(outputs),
+            signer_public_key: transaction_signer.get_public_key().unwrap().as_hex(),
+            batcher_public_key: self.batch_signer.get_public_key().unwrap().as_hex(),
+            dependencies: ::protobuf::RepeatedField::from_vec(dependencies),
+            payload_sha512: sha512(&payload_bytes),
+            // Keeps otherwise identical transactions from sharing an id
+            nonce: Uuid::new_v4().to_hyphenated().to_string(),
+            ..Default::default()
+        };
+
+        let transaction_header_bytes = transaction_header.write_to_bytes().unwrap();
+
+        let transaction = Transaction {
+            header: transaction_header_bytes.clone(),
+            header_signature: transaction_signer.sign(&transaction_header_bytes).unwrap(),
+            payload: payload_bytes,
+            ..Default::default()
+        };
+
+        let transaction_id = transaction.header_signature.clone();
+        self.transactions.push(transaction);
+        transaction_id
+    }
+
+    pub fn add_create_agent(
+        &mut self,
+        transaction_signer: &Signer,
+        name: &str,
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let create_agent = CreateAgentAction {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        let inputs = vec![agent_address.clone()];
+
+        let outputs = vec![agent_address];
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::CREATE_AGENT,
+            create_agent: ::protobuf::SingularPtrField::some(create_agent),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_create_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        latitude: i64,
+        longitude: i64,
+        bounds: Vec<PropertyBound>,
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let record_address = addresser::get_record_address(record_id);
+        let create_record = CreateRecordAction {
+            record_id: record_id.to_string(),
+            latitude,
+            longitude,
+            bounds: ::protobuf::RepeatedField::from_vec(bounds),
+            ..Default::default()
+        };
+
+        let inputs = vec![agent_address, record_address.clone()];
+
+        let outputs = vec![record_address];
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::CREATE_RECORD,
+            create_record: ::protobuf::SingularPtrField::some(create_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_update_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        latitude: i64,
+        longitude: i64,
+        properties: Vec<PropertyValue>,
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let record_address = addresser::get_record_address(record_id);
+        let update_record = UpdateRecordAction {
+            record_id: record_id.to_string(),
+            latitude,
+            longitude,
+            properties: ::protobuf::RepeatedField::from_vec(properties),
+            ..Default::default()
+        };
+
+        let inputs = vec![agent_address, record_address.clone()];
+
+        let outputs = vec![record_address];
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::UPDATE_RECORD,
+            update_record: ::protobuf::SingularPtrField::some(update_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    pub fn add_transfer_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        receiving_agent: &str,
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let owner_address = addresser::get_agent_address(&public_key.as_hex());
+        let receiving_address = addresser::get_agent_address(receiving_agent);
+        let record_address = addresser::get_record_address(record_id);
+        let transfer_record = TransferRecordAction {
+            record_id: record_id.to_string(),
+            receiving_agent: receiving_agent.to_string(),
+            ..Default::default()
+        };
+
+        let inputs = vec![owner_address, receiving_address, record_address.clone()];
+
+        let outputs = vec![record_address];
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::TRANSFER_RECORD,
+            transfer_record: ::protobuf::SingularPtrField::some(transfer_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    pub fn add_assemble_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        component_ids: &[String],
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let assemble_record = AssembleRecordAction {
+            record_id: record_id.to_string(),
+            component_ids: ::protobuf::RepeatedField::from_slice(component_ids),
+            ..Default::default()
+        };
+
+        let mut outputs = vec![addresser::get_record_address(record_id)];
+        outputs.extend(
+            component_ids
+                .iter()
+                .map(|component_id| addresser::get_record_address(component_id)),
+        );
+
+        // Checking that the assembly doesn't end up inside one of its own
+        // components reads every record it is assembled into, so all records
+        // are readable
+        let mut inputs = vec![agent_address];
+        inputs.extend(outputs.iter().cloned());
+        inputs.push(addresser::get_record_prefix());
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::ASSEMBLE_RECORD,
+            assemble_record: ::protobuf::SingularPtrField::some(assemble_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    pub fn add_disassemble_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        component_ids: &[String],
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let disassemble_record = DisassembleRecordAction {
+            record_id: record_id.to_string(),
+            component_ids: ::protobuf::RepeatedField::from_slice(component_ids),
+            ..Default::default()
+        };
+
+        let mut outputs = vec![addresser::get_record_address(record_id)];
+        outputs.extend(
+            component_ids
+                .iter()
+                .map(|component_id| addresser::get_record_address(component_id)),
+        );
+
+        let mut inputs = vec![agent_address];
+        inputs.extend(outputs.iter().cloned());
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::DISASSEMBLE_RECORD,
+            disassemble_record: ::protobuf::SingularPtrField::some(disassemble_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    pub fn add_recall_record(
+        &mut self,
+        transaction_signer: &Signer,
+        record_id: &str,
+        timestamp: i64,
+        dependencies: Vec<String>,
+    ) -> String {
+        let public_key = transaction_signer.get_public_key().unwrap();
+        let agent_address = addresser::get_agent_address(&public_key.as_hex());
+        let record_address = addresser::get_record_address(record_id);
+        let recall_record = RecallRecordAction {
+            record_id: record_id.to_string(),
+            ..Default::default()
+        };
+
+        let inputs = vec![agent_address, record_address.clone()];
+
+        let outputs = vec![record_address];
+
+        let payload = SimpleSupplyPayload {
+            action: SimpleSupplyPayload_Action::RECALL_RECORD,
+            recall_record: ::protobuf::SingularPtrField::some(recall_record),
+            timestamp: timestamp as u64,
+            ..Default::default()
+        };
+        let payload_bytes = payload.write_to_bytes().unwrap();
+
+        self.add_transaction(
+            transaction_signer,
+            payload_bytes,
+            inputs,
+            outputs,
+            dependencies,
+        )
+    }
+
+    pub fn build(self) -> Batch {
+        let batch_header = BatchHeader {
+            signer_public_key: self.batch_signer.get_public_key().unwrap().as_hex(),
+            transaction_ids: ::protobuf::RepeatedField::from_vec(
+                self.transactions
+                    .iter()
+                    .map(|transaction| transaction.header_signature.clone())
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        let batch_header_bytes = batch_header.write_to_bytes().unwrap();
+
+        Batch {
+            header: batch_header_bytes.clone(),
+            header_signature: self.batch_signer.sign(&batch_header_bytes).unwrap(),
+            transactions: ::protobuf::RepeatedField::from_vec(self.transactions),
+            ..Default::default()
+        }
     }
 }
 
@@ -92,3 +501,358 @@ pub fn sha512(src: &[u8]) -> String {
     sha.input(src);
     sha.result_str().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sawtooth_sdk::signing::secp256k1::Secp256k1PublicKey;
+    use sawtooth_sdk::signing::{create_context, Context, PrivateKey};
+
+    struct Keys {
+        context: Box<dyn Context>,
+        transaction_key: Box<dyn PrivateKey>,
+        batch_key: Box<dyn PrivateKey>,
+    }
+
+    impl Keys {
+        fn new() -> Keys {
+            let context = create_context("secp256k1").unwrap();
+            let transaction_key = context.new_random_private_key().unwrap();
+            let batch_key = context.new_random_private_key().unwrap();
+            Keys {
+                context,
+                transaction_key,
+                batch_key,
+            }
+        }
+
+        fn public_key(&self, private_key: &dyn PrivateKey) -> String {
+            self.context.get_public_key(private_key).unwrap().as_hex()
+        }
+
+        fn verify(&self, signature: &str, message: &[u8], public_key: &str) -> bool {
+            let public_key = Secp256k1PublicKey::from_hex(public_key).unwrap();
+            self.context
+                .verify(signature, message, &public_key)
+                .unwrap()
+        }
+    }
+
+    /// Checks every signature in a single-transaction batch and returns the
+    /// transaction header and payload
+    fn check_batch(keys: &Keys, batch: &Batch) -> (TransactionHeader, SimpleSupplyPayload) {
+        let transaction_public_key = keys.public_key(&*keys.transaction_key);
+        let batch_public_key = keys.public_key(&*keys.batch_key);
+
+        let batch_header: BatchHeader = protobuf::parse_from_bytes(batch.get_header()).unwrap();
+        assert_eq!(batch_header.get_signer_public_key(), batch_public_key);
+        assert!(keys.verify(
+            batch.get_header_signature(),
+            batch.get_header(),
+            &batch_public_key
+        ));
+
+        assert_eq!(batch.get_transactions().len(), 1);
+        let transaction = &batch.get_transactions()[0];
+        assert_eq!(
+            batch_header.get_transaction_ids(),
+            &[transaction.get_header_signature().to_string()]
+        );
+
+        let header: TransactionHeader =
+            protobuf::parse_from_bytes(transaction.get_header()).unwrap();
+        assert_eq!(header.get_family_name(), addresser::FAMILY_NAME);
+        assert_eq!(header.get_family_version(), addresser::FAMILY_VERSION);
+        assert_eq!(header.get_signer_public_key(), transaction_public_key);
+        assert_eq!(header.get_batcher_public_key(), batch_public_key);
+        assert_eq!(
+            header.get_payload_sha512(),
+            sha512(transaction.get_payload())
+        );
+        assert!(keys.verify(
+            transaction.get_header_signature(),
+            transaction.get_header(),
+            &transaction_public_key
+        ));
+
+        let payload: SimpleSupplyPayload =
+            protobuf::parse_from_bytes(transaction.get_payload()).unwrap();
+        (header, payload)
+    }
+
+    fn signers(keys: &Keys) -> (Signer<'_>, Signer<'_>) {
+        (
+            Signer::new(&*keys.context, &*keys.transaction_key),
+            Signer::new(&*keys.context, &*keys.batch_key),
+        )
+    }
+
+    #[test]
+    fn create_agent_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let batch = make_create_agent_transaction(transaction_signer, batch_signer, "alice", 10);
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        assert_eq!(header.get_inputs(), &[agent_address]);
+        assert_eq!(header.get_outputs(), header.get_inputs());
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::CREATE_AGENT
+        );
+        assert_eq!(payload.get_create_agent().get_name(), "alice");
+        assert_eq!(payload.get_timestamp(), 10);
+    }
+
+    #[test]
+    fn create_record_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let bound = PropertyBound {
+            name: "temperature".to_string(),
+            min: -5_000_000,
+            max: 5_000_000,
+            ..Default::default()
+        };
+        let batch = make_create_record_transaction(
+            transaction_signer,
+            batch_signer,
+            "fish-1",
+            35_000_000,
+            139_000_000,
+            vec![bound.clone()],
+            20,
+        );
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let record_address = addresser::get_record_address("fish-1");
+        assert_eq!(
+            header.get_inputs(),
+            &[agent_address, record_address.clone()]
+        );
+        assert_eq!(header.get_outputs(), &[record_address]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::CREATE_RECORD
+        );
+        let action = payload.get_create_record();
+        assert_eq!(action.get_record_id(), "fish-1");
+        assert_eq!(action.get_latitude(), 35_000_000);
+        assert_eq!(action.get_longitude(), 139_000_000);
+        assert_eq!(action.get_bounds(), &[bound]);
+    }
+
+    #[test]
+    fn update_record_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let property = PropertyValue {
+            name: "temperature".to_string(),
+            value: 3_500_000,
+            ..Default::default()
+        };
+        let batch = make_update_record_transaction(
+            transaction_signer,
+            batch_signer,
+            "fish-1",
+            -33_000_000,
+            151_000_000,
+            vec![property.clone()],
+            30,
+        );
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let record_address = addresser::get_record_address("fish-1");
+        assert_eq!(
+            header.get_inputs(),
+            &[agent_address, record_address.clone()]
+        );
+        assert_eq!(header.get_outputs(), &[record_address]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::UPDATE_RECORD
+        );
+        let action = payload.get_update_record();
+        assert_eq!(action.get_record_id(), "fish-1");
+        assert_eq!(action.get_latitude(), -33_000_000);
+        assert_eq!(action.get_longitude(), 151_000_000);
+        assert_eq!(action.get_properties(), &[property]);
+    }
+
+    #[test]
+    fn transfer_record_transaction() {
+        let keys = Keys::new();
+        let receiving_key = keys.context.new_random_private_key().unwrap();
+        let receiving_agent = keys.public_key(&*receiving_key);
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let batch = make_transfer_record_transaction(
+            transaction_signer,
+            batch_signer,
+            "fish-1",
+            &receiving_agent,
+            40,
+        );
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let owner_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let receiving_address = addresser::get_agent_address(&receiving_agent);
+        let record_address = addresser::get_record_address("fish-1");
+        assert_eq!(
+            header.get_inputs(),
+            &[owner_address, receiving_address, record_address.clone()]
+        );
+        assert_eq!(header.get_outputs(), &[record_address]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::TRANSFER_RECORD
+        );
+        let action = payload.get_transfer_record();
+        assert_eq!(action.get_record_id(), "fish-1");
+        assert_eq!(action.get_receiving_agent(), receiving_agent);
+    }
+
+    #[test]
+    fn assemble_record_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let component_ids = vec!["fish-1".to_string(), "fish-2".to_string()];
+        let batch = make_assemble_record_transaction(
+            transaction_signer,
+            batch_signer,
+            "crate-1",
+            &component_ids,
+            60,
+        );
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let record_addresses = [
+            addresser::get_record_address("crate-1"),
+            addresser::get_record_address("fish-1"),
+            addresser::get_record_address("fish-2"),
+        ];
+        assert_eq!(header.get_inputs()[0], agent_address);
+        assert_eq!(&header.get_inputs()[1..4], &record_addresses[..]);
+        assert_eq!(header.get_inputs()[4], addresser::get_record_prefix());
+        assert_eq!(header.get_outputs(), &record_addresses[..]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::ASSEMBLE_RECORD
+        );
+        let action = payload.get_assemble_record();
+        assert_eq!(action.get_record_id(), "crate-1");
+        assert_eq!(action.get_component_ids(), &component_ids[..]);
+        assert_eq!(payload.get_timestamp(), 60);
+    }
+
+    #[test]
+    fn disassemble_record_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let component_ids = vec!["fish-1".to_string()];
+        let batch = make_disassemble_record_transaction(
+            transaction_signer,
+            batch_signer,
+            "crate-1",
+            &component_ids,
+            70,
+        );
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let assembly_address = addresser::get_record_address("crate-1");
+        let component_address = addresser::get_record_address("fish-1");
+        assert_eq!(
+            header.get_inputs(),
+            &[
+                agent_address,
+                assembly_address.clone(),
+                component_address.clone()
+            ]
+        );
+        assert_eq!(header.get_outputs(), &[assembly_address, component_address]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::DISASSEMBLE_RECORD
+        );
+        let action = payload.get_disassemble_record();
+        assert_eq!(action.get_record_id(), "crate-1");
+        assert_eq!(action.get_component_ids(), &component_ids[..]);
+    }
+
+    #[test]
+    fn recall_record_transaction() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let batch = make_recall_record_transaction(transaction_signer, batch_signer, "fish-1", 80);
+
+        let (header, payload) = check_batch(&keys, &batch);
+        let agent_address = addresser::get_agent_address(&keys.public_key(&*keys.transaction_key));
+        let record_address = addresser::get_record_address("fish-1");
+        assert_eq!(
+            header.get_inputs(),
+            &[agent_address, record_address.clone()]
+        );
+        assert_eq!(header.get_outputs(), &[record_address]);
+        assert_eq!(
+            payload.get_action(),
+            SimpleSupplyPayload_Action::RECALL_RECORD
+        );
+        assert_eq!(payload.get_recall_record().get_record_id(), "fish-1");
+    }
+
+    #[test]
+    fn batch_with_dependent_transactions() {
+        let keys = Keys::new();
+        let (transaction_signer, batch_signer) = signers(&keys);
+        let mut builder = BatchBuilder::new(batch_signer);
+        let create_id = builder.add_create_record(
+            &transaction_signer,
+            "fish-1",
+            0,
+            0,
+            Vec::new(),
+            50,
+            Vec::new(),
+        );
+        let update_id = builder.add_update_record(
+            &transaction_signer,
+            "fish-1",
+            1,
+            1,
+            Vec::new(),
+            60,
+            vec![create_id.clone()],
+        );
+        let transfer_id = builder.add_transfer_record(
+            &transaction_signer,
+            "fish-1",
+            "receiver",
+            70,
+            vec![create_id.clone(), update_id.clone()],
+        );
+        let batch = builder.build();
+
+        let batch_header: BatchHeader = protobuf::parse_from_bytes(batch.get_header()).unwrap();
+        assert_eq!(
+            batch_header.get_transaction_ids(),
+            &[create_id.clone(), update_id.clone(), transfer_id]
+        );
+        let dependencies: Vec<Vec<String>> = batch
+            .get_transactions()
+            .iter()
+            .map(|transaction| {
+                let header: TransactionHeader =
+                    protobuf::parse_from_bytes(transaction.get_header()).unwrap();
+                header.get_dependencies().to_vec()
+            })
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![vec![], vec![create_id.clone()], vec![create_id, update_id]]
+        );
+    }
+}
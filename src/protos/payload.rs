@@ -35,6 +35,9 @@ pub struct SimpleSupplyPayload {
     pub update_record: ::protobuf::SingularPtrField<UpdateRecordAction>,
     pub transfer_record: ::protobuf::SingularPtrField<TransferRecordAction>,
     pub timestamp: u64,
+    pub assemble_record: ::protobuf::SingularPtrField<AssembleRecordAction>,
+    pub disassemble_record: ::protobuf::SingularPtrField<DisassembleRecordAction>,
+    pub recall_record: ::protobuf::SingularPtrField<RecallRecordAction>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -212,6 +215,105 @@ impl SimpleSupplyPayload {
     pub fn set_timestamp(&mut self, v: u64) {
         self.timestamp = v;
     }
+
+    // .AssembleRecordAction assemble_record = 7;
+
+
+    pub fn get_assemble_record(&self) -> &AssembleRecordAction {
+        self.assemble_record.as_ref().unwrap_or_else(|| AssembleRecordAction::default_instance())
+    }
+    pub fn clear_assemble_record(&mut self) {
+        self.assemble_record.clear();
+    }
+
+    pub fn has_assemble_record(&self) -> bool {
+        self.assemble_record.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_assemble_record(&mut self, v: AssembleRecordAction) {
+        self.assemble_record = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_assemble_record(&mut self) -> &mut AssembleRecordAction {
+        if self.assemble_record.is_none() {
+            self.assemble_record.set_default();
+        }
+        self.assemble_record.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_assemble_record(&mut self) -> AssembleRecordAction {
+        self.assemble_record.take().unwrap_or_else(|| AssembleRecordAction::new())
+    }
+
+    // .DisassembleRecordAction disassemble_record = 8;
+
+
+    pub fn get_disassemble_record(&self) -> &DisassembleRecordAction {
+        self.disassemble_record.as_ref().unwrap_or_else(|| DisassembleRecordAction::default_instance())
+    }
+    pub fn clear_disassemble_record(&mut self) {
+        self.disassemble_record.clear();
+    }
+
+    pub fn has_disassemble_record(&self) -> bool {
+        self.disassemble_record.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_disassemble_record(&mut self, v: DisassembleRecordAction) {
+        self.disassemble_record = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_disassemble_record(&mut self) -> &mut DisassembleRecordAction {
+        if self.disassemble_record.is_none() {
+            self.disassemble_record.set_default();
+        }
+        self.disassemble_record.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_disassemble_record(&mut self) -> DisassembleRecordAction {
+        self.disassemble_record.take().unwrap_or_else(|| DisassembleRecordAction::new())
+    }
+
+    // .RecallRecordAction recall_record = 9;
+
+
+    pub fn get_recall_record(&self) -> &RecallRecordAction {
+        self.recall_record.as_ref().unwrap_or_else(|| RecallRecordAction::default_instance())
+    }
+    pub fn clear_recall_record(&mut self) {
+        self.recall_record.clear();
+    }
+
+    pub fn has_recall_record(&self) -> bool {
+        self.recall_record.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_recall_record(&mut self, v: RecallRecordAction) {
+        self.recall_record = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_recall_record(&mut self) -> &mut RecallRecordAction {
+        if self.recall_record.is_none() {
+            self.recall_record.set_default();
+        }
+        self.recall_record.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_recall_record(&mut self) -> RecallRecordAction {
+        self.recall_record.take().unwrap_or_else(|| RecallRecordAction::new())
+    }
 }
 
 impl ::protobuf::Message for SimpleSupplyPayload {
@@ -236,6 +338,21 @@ impl ::protobuf::Message for SimpleSupplyPayload {
                 return false;
             }
         };
+        for v in &self.assemble_record {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.disassemble_record {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.recall_record {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -265,6 +382,15 @@ impl ::protobuf::Message for SimpleSupplyPayload {
                     let tmp = is.read_uint64()?;
                     self.timestamp = tmp;
                 },
+                7 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.assemble_record)?;
+                },
+                8 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.disassemble_record)?;
+                },
+                9 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.recall_record)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -299,6 +425,18 @@ impl ::protobuf::Message for SimpleSupplyPayload {
         if self.timestamp != 0 {
             my_size += ::protobuf::rt::value_size(6, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
         }
+        if let Some(ref v) = self.assemble_record.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if let Some(ref v) = self.disassemble_record.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if let Some(ref v) = self.recall_record.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -331,6 +469,21 @@ impl ::protobuf::Message for SimpleSupplyPayload {
         if self.timestamp != 0 {
             os.write_uint64(6, self.timestamp)?;
         }
+        if let Some(ref v) = self.assemble_record.as_ref() {
+            os.write_tag(7, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if let Some(ref v) = self.disassemble_record.as_ref() {
+            os.write_tag(8, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if let Some(ref v) = self.recall_record.as_ref() {
+            os.write_tag(9, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -403,6 +556,21 @@ impl ::protobuf::Message for SimpleSupplyPayload {
                     |m: &SimpleSupplyPayload| { &m.timestamp },
                     |m: &mut SimpleSupplyPayload| { &mut m.timestamp },
                 ));
+                fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<AssembleRecordAction>>(
+                    "assemble_record",
+                    |m: &SimpleSupplyPayload| { &m.assemble_record },
+                    |m: &mut SimpleSupplyPayload| { &mut m.assemble_record },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<DisassembleRecordAction>>(
+                    "disassemble_record",
+                    |m: &SimpleSupplyPayload| { &m.disassemble_record },
+                    |m: &mut SimpleSupplyPayload| { &mut m.disassemble_record },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<RecallRecordAction>>(
+                    "recall_record",
+                    |m: &SimpleSupplyPayload| { &m.recall_record },
+                    |m: &mut SimpleSupplyPayload| { &mut m.recall_record },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<SimpleSupplyPayload>(
                     "SimpleSupplyPayload",
                     fields,
@@ -431,6 +599,9 @@ impl ::protobuf::Clear for SimpleSupplyPayload {
         self.update_record.clear();
         self.transfer_record.clear();
         self.timestamp = 0;
+        self.assemble_record.clear();
+        self.disassemble_record.clear();
+        self.recall_record.clear();
         self.unknown_fields.clear();
     }
 }
@@ -453,6 +624,9 @@ pub enum SimpleSupplyPayload_Action {
     CREATE_RECORD = 1,
     UPDATE_RECORD = 2,
     TRANSFER_RECORD = 3,
+    ASSEMBLE_RECORD = 4,
+    DISASSEMBLE_RECORD = 5,
+    RECALL_RECORD = 6,
 }
 
 impl ::protobuf::ProtobufEnum for SimpleSupplyPayload_Action {
@@ -466,6 +640,9 @@ impl ::protobuf::ProtobufEnum for SimpleSupplyPayload_Action {
             1 => ::std::option::Option::Some(SimpleSupplyPayload_Action::CREATE_RECORD),
             2 => ::std::option::Option::Some(SimpleSupplyPayload_Action::UPDATE_RECORD),
             3 => ::std::option::Option::Some(SimpleSupplyPayload_Action::TRANSFER_RECORD),
+            4 => ::std::option::Option::Some(SimpleSupplyPayload_Action::ASSEMBLE_RECORD),
+            5 => ::std::option::Option::Some(SimpleSupplyPayload_Action::DISASSEMBLE_RECORD),
+            6 => ::std::option::Option::Some(SimpleSupplyPayload_Action::RECALL_RECORD),
             _ => ::std::option::Option::None
         }
     }
@@ -476,6 +653,9 @@ impl ::protobuf::ProtobufEnum for SimpleSupplyPayload_Action {
             SimpleSupplyPayload_Action::CREATE_RECORD,
             SimpleSupplyPayload_Action::UPDATE_RECORD,
             SimpleSupplyPayload_Action::TRANSFER_RECORD,
+            SimpleSupplyPayload_Action::ASSEMBLE_RECORD,
+            SimpleSupplyPayload_Action::DISASSEMBLE_RECORD,
+            SimpleSupplyPayload_Action::RECALL_RECORD,
         ];
         values
     }
@@ -683,6 +863,7 @@ pub struct CreateRecordAction {
     pub record_id: ::std::string::String,
     pub latitude: i64,
     pub longitude: i64,
+    pub bounds: ::protobuf::RepeatedField<PropertyBound>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -754,10 +935,40 @@ impl CreateRecordAction {
     pub fn set_longitude(&mut self, v: i64) {
         self.longitude = v;
     }
+
+    // repeated .PropertyBound bounds = 4;
+
+
+    pub fn get_bounds(&self) -> &[PropertyBound] {
+        &self.bounds
+    }
+    pub fn clear_bounds(&mut self) {
+        self.bounds.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_bounds(&mut self, v: ::protobuf::RepeatedField<PropertyBound>) {
+        self.bounds = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_bounds(&mut self) -> &mut ::protobuf::RepeatedField<PropertyBound> {
+        &mut self.bounds
+    }
+
+    // Take field
+    pub fn take_bounds(&mut self) -> ::protobuf::RepeatedField<PropertyBound> {
+        ::std::mem::replace(&mut self.bounds, ::protobuf::RepeatedField::new())
+    }
 }
 
 impl ::protobuf::Message for CreateRecordAction {
     fn is_initialized(&self) -> bool {
+        for v in &self.bounds {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -782,6 +993,9 @@ impl ::protobuf::Message for CreateRecordAction {
                     let tmp = is.read_sint64()?;
                     self.longitude = tmp;
                 },
+                4 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.bounds)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -803,6 +1017,10 @@ impl ::protobuf::Message for CreateRecordAction {
         if self.longitude != 0 {
             my_size += ::protobuf::rt::value_varint_zigzag_size(3, self.longitude);
         }
+        for value in &self.bounds {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -818,6 +1036,11 @@ impl ::protobuf::Message for CreateRecordAction {
         if self.longitude != 0 {
             os.write_sint64(3, self.longitude)?;
         }
+        for v in &self.bounds {
+            os.write_tag(4, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -875,6 +1098,11 @@ impl ::protobuf::Message for CreateRecordAction {
                     |m: &CreateRecordAction| { &m.longitude },
                     |m: &mut CreateRecordAction| { &mut m.longitude },
                 ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<PropertyBound>>(
+                    "bounds",
+                    |m: &CreateRecordAction| { &m.bounds },
+                    |m: &mut CreateRecordAction| { &mut m.bounds },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<CreateRecordAction>(
                     "CreateRecordAction",
                     fields,
@@ -900,6 +1128,7 @@ impl ::protobuf::Clear for CreateRecordAction {
         self.record_id.clear();
         self.latitude = 0;
         self.longitude = 0;
+        self.bounds.clear();
         self.unknown_fields.clear();
     }
 }
@@ -922,6 +1151,7 @@ pub struct UpdateRecordAction {
     pub record_id: ::std::string::String,
     pub latitude: i64,
     pub longitude: i64,
+    pub properties: ::protobuf::RepeatedField<PropertyValue>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -993,10 +1223,40 @@ impl UpdateRecordAction {
     pub fn set_longitude(&mut self, v: i64) {
         self.longitude = v;
     }
+
+    // repeated .PropertyValue properties = 4;
+
+
+    pub fn get_properties(&self) -> &[PropertyValue] {
+        &self.properties
+    }
+    pub fn clear_properties(&mut self) {
+        self.properties.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_properties(&mut self, v: ::protobuf::RepeatedField<PropertyValue>) {
+        self.properties = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_properties(&mut self) -> &mut ::protobuf::RepeatedField<PropertyValue> {
+        &mut self.properties
+    }
+
+    // Take field
+    pub fn take_properties(&mut self) -> ::protobuf::RepeatedField<PropertyValue> {
+        ::std::mem::replace(&mut self.properties, ::protobuf::RepeatedField::new())
+    }
 }
 
 impl ::protobuf::Message for UpdateRecordAction {
     fn is_initialized(&self) -> bool {
+        for v in &self.properties {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -1021,6 +1281,9 @@ impl ::protobuf::Message for UpdateRecordAction {
                     let tmp = is.read_sint64()?;
                     self.longitude = tmp;
                 },
+                4 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.properties)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -1042,6 +1305,10 @@ impl ::protobuf::Message for UpdateRecordAction {
         if self.longitude != 0 {
             my_size += ::protobuf::rt::value_varint_zigzag_size(3, self.longitude);
         }
+        for value in &self.properties {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -1057,6 +1324,11 @@ impl ::protobuf::Message for UpdateRecordAction {
         if self.longitude != 0 {
             os.write_sint64(3, self.longitude)?;
         }
+        for v in &self.properties {
+            os.write_tag(4, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1114,6 +1386,11 @@ impl ::protobuf::Message for UpdateRecordAction {
                     |m: &UpdateRecordAction| { &m.longitude },
                     |m: &mut UpdateRecordAction| { &mut m.longitude },
                 ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<PropertyValue>>(
+                    "properties",
+                    |m: &UpdateRecordAction| { &m.properties },
+                    |m: &mut UpdateRecordAction| { &mut m.properties },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<UpdateRecordAction>(
                     "UpdateRecordAction",
                     fields,
@@ -1139,6 +1416,7 @@ impl ::protobuf::Clear for UpdateRecordAction {
         self.record_id.clear();
         self.latitude = 0;
         self.longitude = 0;
+        self.properties.clear();
         self.unknown_fields.clear();
     }
 }
@@ -1366,26 +1644,1074 @@ impl ::protobuf::reflect::ProtobufValue for TransferRecordAction {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct AssembleRecordAction {
+    // message fields
+    pub record_id: ::std::string::String,
+    pub component_ids: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a AssembleRecordAction {
+    fn default() -> &'a AssembleRecordAction {
+        <AssembleRecordAction as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl AssembleRecordAction {
+    pub fn new() -> AssembleRecordAction {
+        ::std::default::Default::default()
+    }
+
+    // string record_id = 1;
+
+
+    pub fn get_record_id(&self) -> &str {
+        &self.record_id
+    }
+    pub fn clear_record_id(&mut self) {
+        self.record_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_record_id(&mut self, v: ::std::string::String) {
+        self.record_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_record_id(&mut self) -> &mut ::std::string::String {
+        &mut self.record_id
+    }
+
+    // Take field
+    pub fn take_record_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.record_id, ::std::string::String::new())
+    }
+
+    // repeated string component_ids = 2;
+
+
+    pub fn get_component_ids(&self) -> &[::std::string::String] {
+        &self.component_ids
+    }
+    pub fn clear_component_ids(&mut self) {
+        self.component_ids.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_component_ids(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.component_ids = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_component_ids(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.component_ids
+    }
+
+    // Take field
+    pub fn take_component_ids(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.component_ids, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for AssembleRecordAction {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.record_id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.component_ids)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.record_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.record_id);
+        }
+        for value in &self.component_ids {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.record_id.is_empty() {
+            os.write_string(1, &self.record_id)?;
+        }
+        for v in &self.component_ids {
+            os.write_string(2, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AssembleRecordAction {
+        AssembleRecordAction::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "record_id",
+                    |m: &AssembleRecordAction| { &m.record_id },
+                    |m: &mut AssembleRecordAction| { &mut m.record_id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "component_ids",
+                    |m: &AssembleRecordAction| { &m.component_ids },
+                    |m: &mut AssembleRecordAction| { &mut m.component_ids },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<AssembleRecordAction>(
+                    "AssembleRecordAction",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static AssembleRecordAction {
+        static mut instance: ::protobuf::lazy::Lazy<AssembleRecordAction> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const AssembleRecordAction,
+        };
+        unsafe {
+            instance.get(AssembleRecordAction::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for AssembleRecordAction {
+    fn clear(&mut self) {
+        self.record_id.clear();
+        self.component_ids.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AssembleRecordAction {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AssembleRecordAction {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct DisassembleRecordAction {
+    // message fields
+    pub record_id: ::std::string::String,
+    pub component_ids: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a DisassembleRecordAction {
+    fn default() -> &'a DisassembleRecordAction {
+        <DisassembleRecordAction as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl DisassembleRecordAction {
+    pub fn new() -> DisassembleRecordAction {
+        ::std::default::Default::default()
+    }
+
+    // string record_id = 1;
+
+
+    pub fn get_record_id(&self) -> &str {
+        &self.record_id
+    }
+    pub fn clear_record_id(&mut self) {
+        self.record_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_record_id(&mut self, v: ::std::string::String) {
+        self.record_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_record_id(&mut self) -> &mut ::std::string::String {
+        &mut self.record_id
+    }
+
+    // Take field
+    pub fn take_record_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.record_id, ::std::string::String::new())
+    }
+
+    // repeated string component_ids = 2;
+
+
+    pub fn get_component_ids(&self) -> &[::std::string::String] {
+        &self.component_ids
+    }
+    pub fn clear_component_ids(&mut self) {
+        self.component_ids.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_component_ids(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.component_ids = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_component_ids(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.component_ids
+    }
+
+    // Take field
+    pub fn take_component_ids(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.component_ids, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for DisassembleRecordAction {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.record_id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.component_ids)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.record_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.record_id);
+        }
+        for value in &self.component_ids {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.record_id.is_empty() {
+            os.write_string(1, &self.record_id)?;
+        }
+        for v in &self.component_ids {
+            os.write_string(2, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DisassembleRecordAction {
+        DisassembleRecordAction::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "record_id",
+                    |m: &DisassembleRecordAction| { &m.record_id },
+                    |m: &mut DisassembleRecordAction| { &mut m.record_id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "component_ids",
+                    |m: &DisassembleRecordAction| { &m.component_ids },
+                    |m: &mut DisassembleRecordAction| { &mut m.component_ids },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<DisassembleRecordAction>(
+                    "DisassembleRecordAction",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static DisassembleRecordAction {
+        static mut instance: ::protobuf::lazy::Lazy<DisassembleRecordAction> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const DisassembleRecordAction,
+        };
+        unsafe {
+            instance.get(DisassembleRecordAction::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for DisassembleRecordAction {
+    fn clear(&mut self) {
+        self.record_id.clear();
+        self.component_ids.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DisassembleRecordAction {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DisassembleRecordAction {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct RecallRecordAction {
+    // message fields
+    pub record_id: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a RecallRecordAction {
+    fn default() -> &'a RecallRecordAction {
+        <RecallRecordAction as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl RecallRecordAction {
+    pub fn new() -> RecallRecordAction {
+        ::std::default::Default::default()
+    }
+
+    // string record_id = 1;
+
+
+    pub fn get_record_id(&self) -> &str {
+        &self.record_id
+    }
+    pub fn clear_record_id(&mut self) {
+        self.record_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_record_id(&mut self, v: ::std::string::String) {
+        self.record_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_record_id(&mut self) -> &mut ::std::string::String {
+        &mut self.record_id
+    }
+
+    // Take field
+    pub fn take_record_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.record_id, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for RecallRecordAction {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.record_id)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.record_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.record_id);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.record_id.is_empty() {
+            os.write_string(1, &self.record_id)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> RecallRecordAction {
+        RecallRecordAction::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "record_id",
+                    |m: &RecallRecordAction| { &m.record_id },
+                    |m: &mut RecallRecordAction| { &mut m.record_id },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<RecallRecordAction>(
+                    "RecallRecordAction",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static RecallRecordAction {
+        static mut instance: ::protobuf::lazy::Lazy<RecallRecordAction> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const RecallRecordAction,
+        };
+        unsafe {
+            instance.get(RecallRecordAction::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for RecallRecordAction {
+    fn clear(&mut self) {
+        self.record_id.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for RecallRecordAction {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for RecallRecordAction {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct PropertyBound {
+    // message fields
+    pub name: ::std::string::String,
+    pub min: i64,
+    pub max: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a PropertyBound {
+    fn default() -> &'a PropertyBound {
+        <PropertyBound as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl PropertyBound {
+    pub fn new() -> PropertyBound {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // sint64 min = 2;
+
+
+    pub fn get_min(&self) -> i64 {
+        self.min
+    }
+    pub fn clear_min(&mut self) {
+        self.min = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_min(&mut self, v: i64) {
+        self.min = v;
+    }
+
+    // sint64 max = 3;
+
+
+    pub fn get_max(&self) -> i64 {
+        self.max
+    }
+    pub fn clear_max(&mut self) {
+        self.max = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_max(&mut self, v: i64) {
+        self.max = v;
+    }
+}
+
+impl ::protobuf::Message for PropertyBound {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.min = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.max = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        if self.min != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(2, self.min);
+        }
+        if self.max != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(3, self.max);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        if self.min != 0 {
+            os.write_sint64(2, self.min)?;
+        }
+        if self.max != 0 {
+            os.write_sint64(3, self.max)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> PropertyBound {
+        PropertyBound::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "name",
+                    |m: &PropertyBound| { &m.name },
+                    |m: &mut PropertyBound| { &mut m.name },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "min",
+                    |m: &PropertyBound| { &m.min },
+                    |m: &mut PropertyBound| { &mut m.min },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "max",
+                    |m: &PropertyBound| { &m.max },
+                    |m: &mut PropertyBound| { &mut m.max },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<PropertyBound>(
+                    "PropertyBound",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static PropertyBound {
+        static mut instance: ::protobuf::lazy::Lazy<PropertyBound> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const PropertyBound,
+        };
+        unsafe {
+            instance.get(PropertyBound::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for PropertyBound {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.min = 0;
+        self.max = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for PropertyBound {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PropertyBound {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct PropertyValue {
+    // message fields
+    pub name: ::std::string::String,
+    pub value: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a PropertyValue {
+    fn default() -> &'a PropertyValue {
+        <PropertyValue as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl PropertyValue {
+    pub fn new() -> PropertyValue {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // sint64 value = 2;
+
+
+    pub fn get_value(&self) -> i64 {
+        self.value
+    }
+    pub fn clear_value(&mut self) {
+        self.value = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_value(&mut self, v: i64) {
+        self.value = v;
+    }
+}
+
+impl ::protobuf::Message for PropertyValue {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.value = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        if self.value != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(2, self.value);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        if self.value != 0 {
+            os.write_sint64(2, self.value)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> PropertyValue {
+        PropertyValue::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "name",
+                    |m: &PropertyValue| { &m.name },
+                    |m: &mut PropertyValue| { &mut m.name },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "value",
+                    |m: &PropertyValue| { &m.value },
+                    |m: &mut PropertyValue| { &mut m.value },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<PropertyValue>(
+                    "PropertyValue",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static PropertyValue {
+        static mut instance: ::protobuf::lazy::Lazy<PropertyValue> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const PropertyValue,
+        };
+        unsafe {
+            instance.get(PropertyValue::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for PropertyValue {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.value = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for PropertyValue {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PropertyValue {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\rpayload.proto\"\xaa\x03\n\x13SimpleSupplyPayload\x123\n\x06action\
+    \n\rpayload.proto\"\xae\x05\n\x13SimpleSupplyPayload\x123\n\x06action\
     \x18\x01\x20\x01(\x0e2\x1b.SimpleSupplyPayload.ActionR\x06action\x125\n\
     \x0ccreate_agent\x18\x02\x20\x01(\x0b2\x12.CreateAgentActionR\x0bcreateA\
     gent\x128\n\rcreate_record\x18\x03\x20\x01(\x0b2\x13.CreateRecordActionR\
     \x0ccreateRecord\x128\n\rupdate_record\x18\x04\x20\x01(\x0b2\x13.UpdateR\
     ecordActionR\x0cupdateRecord\x12>\n\x0ftransfer_record\x18\x05\x20\x01(\
     \x0b2\x15.TransferRecordActionR\x0etransferRecord\x12\x1c\n\ttimestamp\
-    \x18\x06\x20\x01(\x04R\ttimestamp\"U\n\x06Action\x12\x10\n\x0cCREATE_AGE\
-    NT\x10\0\x12\x11\n\rCREATE_RECORD\x10\x01\x12\x11\n\rUPDATE_RECORD\x10\
-    \x02\x12\x13\n\x0fTRANSFER_RECORD\x10\x03\"'\n\x11CreateAgentAction\x12\
-    \x12\n\x04name\x18\x01\x20\x01(\tR\x04name\"k\n\x12CreateRecordAction\
-    \x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08recordId\x12\x1a\n\x08latit\
-    ude\x18\x02\x20\x01(\x12R\x08latitude\x12\x1c\n\tlongitude\x18\x03\x20\
-    \x01(\x12R\tlongitude\"k\n\x12UpdateRecordAction\x12\x1b\n\trecord_id\
-    \x18\x01\x20\x01(\tR\x08recordId\x12\x1a\n\x08latitude\x18\x02\x20\x01(\
-    \x12R\x08latitude\x12\x1c\n\tlongitude\x18\x03\x20\x01(\x12R\tlongitude\
-    \"\\\n\x14TransferRecordAction\x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\
-    \x08recordId\x12'\n\x0freceiving_agent\x18\x02\x20\x01(\tR\x0ereceivingA\
-    gentb\x06proto3\
+    \x18\x06\x20\x01(\x04R\ttimestamp\x12>\n\x0fassemble_record\x18\x07\x20\
+    \x01(\x0b2\x15.AssembleRecordActionR\x0eassembleRecord\x12G\n\x12disasse\
+    mble_record\x18\x08\x20\x01(\x0b2\x18.DisassembleRecordActionR\x11disass\
+    embleRecord\x128\n\rrecall_record\x18\t\x20\x01(\x0b2\x13.RecallRecordAc\
+    tionR\x0crecallRecord\"\x95\x01\n\x06Action\x12\x10\n\x0cCREATE_AGENT\
+    \x10\0\x12\x11\n\rCREATE_RECORD\x10\x01\x12\x11\n\rUPDATE_RECORD\x10\x02\
+    \x12\x13\n\x0fTRANSFER_RECORD\x10\x03\x12\x13\n\x0fASSEMBLE_RECORD\x10\
+    \x04\x12\x16\n\x12DISASSEMBLE_RECORD\x10\x05\x12\x11\n\rRECALL_RECORD\
+    \x10\x06\"'\n\x11CreateAgentAction\x12\x12\n\x04name\x18\x01\x20\x01(\tR\
+    \x04name\"\x93\x01\n\x12CreateRecordAction\x12\x1b\n\trecord_id\x18\x01\
+    \x20\x01(\tR\x08recordId\x12\x1a\n\x08latitude\x18\x02\x20\x01(\x12R\x08\
+    latitude\x12\x1c\n\tlongitude\x18\x03\x20\x01(\x12R\tlongitude\x12&\n\
+    \x06bounds\x18\x04\x20\x03(\x0b2\x0e.PropertyBoundR\x06bounds\"\x9b\x01\
+    \n\x12UpdateRecordAction\x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08rec\
+    ordId\x12\x1a\n\x08latitude\x18\x02\x20\x01(\x12R\x08latitude\x12\x1c\n\
+    \tlongitude\x18\x03\x20\x01(\x12R\tlongitude\x12.\n\nproperties\x18\x04\
+    \x20\x03(\x0b2\x0e.PropertyValueR\nproperties\"\\\n\x14TransferRecordAct\
+    ion\x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08recordId\x12'\n\x0frecei\
+    ving_agent\x18\x02\x20\x01(\tR\x0ereceivingAgent\"X\n\x14AssembleRecordA\
+    ction\x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08recordId\x12#\n\rcompo\
+    nent_ids\x18\x02\x20\x03(\tR\x0ccomponentIds\"[\n\x17DisassembleRecordAc\
+    tion\x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08recordId\x12#\n\rcompon\
+    ent_ids\x18\x02\x20\x03(\tR\x0ccomponentIds\"1\n\x12RecallRecordAction\
+    \x12\x1b\n\trecord_id\x18\x01\x20\x01(\tR\x08recordId\"G\n\rPropertyBoun\
+    d\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04name\x12\x10\n\x03min\x18\x02\
+    \x20\x01(\x12R\x03min\x12\x10\n\x03max\x18\x03\x20\x01(\x12R\x03max\"9\n\
+    \rPropertyValue\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04name\x12\x14\n\
+    \x05value\x18\x02\x20\x01(\x12R\x05valueb\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
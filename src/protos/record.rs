@@ -32,6 +32,12 @@ pub struct Record {
     pub record_id: ::std::string::String,
     pub owners: ::protobuf::RepeatedField<Record_Owner>,
     pub locations: ::protobuf::RepeatedField<Record_Location>,
+    pub components: ::protobuf::RepeatedField<::std::string::String>,
+    pub assembly: ::std::string::String,
+    pub recalled: bool,
+    pub bounds: ::protobuf::RepeatedField<Record_PropertyBound>,
+    pub reports: ::protobuf::RepeatedField<Record_PropertyReport>,
+    pub in_violation: bool,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -123,6 +129,137 @@ impl Record {
     pub fn take_locations(&mut self) -> ::protobuf::RepeatedField<Record_Location> {
         ::std::mem::replace(&mut self.locations, ::protobuf::RepeatedField::new())
     }
+
+    // repeated string components = 4;
+
+
+    pub fn get_components(&self) -> &[::std::string::String] {
+        &self.components
+    }
+    pub fn clear_components(&mut self) {
+        self.components.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_components(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.components = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_components(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.components
+    }
+
+    // Take field
+    pub fn take_components(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.components, ::protobuf::RepeatedField::new())
+    }
+
+    // string assembly = 5;
+
+
+    pub fn get_assembly(&self) -> &str {
+        &self.assembly
+    }
+    pub fn clear_assembly(&mut self) {
+        self.assembly.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_assembly(&mut self, v: ::std::string::String) {
+        self.assembly = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_assembly(&mut self) -> &mut ::std::string::String {
+        &mut self.assembly
+    }
+
+    // Take field
+    pub fn take_assembly(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.assembly, ::std::string::String::new())
+    }
+
+    // bool recalled = 6;
+
+
+    pub fn get_recalled(&self) -> bool {
+        self.recalled
+    }
+    pub fn clear_recalled(&mut self) {
+        self.recalled = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_recalled(&mut self, v: bool) {
+        self.recalled = v;
+    }
+
+    // repeated .Record.PropertyBound bounds = 7;
+
+
+    pub fn get_bounds(&self) -> &[Record_PropertyBound] {
+        &self.bounds
+    }
+    pub fn clear_bounds(&mut self) {
+        self.bounds.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_bounds(&mut self, v: ::protobuf::RepeatedField<Record_PropertyBound>) {
+        self.bounds = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_bounds(&mut self) -> &mut ::protobuf::RepeatedField<Record_PropertyBound> {
+        &mut self.bounds
+    }
+
+    // Take field
+    pub fn take_bounds(&mut self) -> ::protobuf::RepeatedField<Record_PropertyBound> {
+        ::std::mem::replace(&mut self.bounds, ::protobuf::RepeatedField::new())
+    }
+
+    // repeated .Record.PropertyReport reports = 8;
+
+
+    pub fn get_reports(&self) -> &[Record_PropertyReport] {
+        &self.reports
+    }
+    pub fn clear_reports(&mut self) {
+        self.reports.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_reports(&mut self, v: ::protobuf::RepeatedField<Record_PropertyReport>) {
+        self.reports = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_reports(&mut self) -> &mut ::protobuf::RepeatedField<Record_PropertyReport> {
+        &mut self.reports
+    }
+
+    // Take field
+    pub fn take_reports(&mut self) -> ::protobuf::RepeatedField<Record_PropertyReport> {
+        ::std::mem::replace(&mut self.reports, ::protobuf::RepeatedField::new())
+    }
+
+    // bool in_violation = 9;
+
+
+    pub fn get_in_violation(&self) -> bool {
+        self.in_violation
+    }
+    pub fn clear_in_violation(&mut self) {
+        self.in_violation = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_in_violation(&mut self, v: bool) {
+        self.in_violation = v;
+    }
 }
 
 impl ::protobuf::Message for Record {
@@ -137,6 +274,16 @@ impl ::protobuf::Message for Record {
                 return false;
             }
         };
+        for v in &self.bounds {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.reports {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -153,6 +300,32 @@ impl ::protobuf::Message for Record {
                 3 => {
                     ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.locations)?;
                 },
+                4 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.components)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.assembly)?;
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.recalled = tmp;
+                },
+                7 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.bounds)?;
+                },
+                8 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.reports)?;
+                },
+                9 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.in_violation = tmp;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -176,6 +349,26 @@ impl ::protobuf::Message for Record {
             let len = value.compute_size();
             my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
         };
+        for value in &self.components {
+            my_size += ::protobuf::rt::string_size(4, &value);
+        };
+        if !self.assembly.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.assembly);
+        }
+        if self.recalled != false {
+            my_size += 2;
+        }
+        for value in &self.bounds {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        for value in &self.reports {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        if self.in_violation != false {
+            my_size += 2;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -195,6 +388,28 @@ impl ::protobuf::Message for Record {
             os.write_raw_varint32(v.get_cached_size())?;
             v.write_to_with_cached_sizes(os)?;
         };
+        for v in &self.components {
+            os.write_string(4, &v)?;
+        };
+        if !self.assembly.is_empty() {
+            os.write_string(5, &self.assembly)?;
+        }
+        if self.recalled != false {
+            os.write_bool(6, self.recalled)?;
+        }
+        for v in &self.bounds {
+            os.write_tag(7, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        for v in &self.reports {
+            os.write_tag(8, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        if self.in_violation != false {
+            os.write_bool(9, self.in_violation)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -252,6 +467,36 @@ impl ::protobuf::Message for Record {
                     |m: &Record| { &m.locations },
                     |m: &mut Record| { &mut m.locations },
                 ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "components",
+                    |m: &Record| { &m.components },
+                    |m: &mut Record| { &mut m.components },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "assembly",
+                    |m: &Record| { &m.assembly },
+                    |m: &mut Record| { &mut m.assembly },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "recalled",
+                    |m: &Record| { &m.recalled },
+                    |m: &mut Record| { &mut m.recalled },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Record_PropertyBound>>(
+                    "bounds",
+                    |m: &Record| { &m.bounds },
+                    |m: &mut Record| { &mut m.bounds },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Record_PropertyReport>>(
+                    "reports",
+                    |m: &Record| { &m.reports },
+                    |m: &mut Record| { &mut m.reports },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "in_violation",
+                    |m: &Record| { &m.in_violation },
+                    |m: &mut Record| { &mut m.in_violation },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Record>(
                     "Record",
                     fields,
@@ -277,6 +522,12 @@ impl ::protobuf::Clear for Record {
         self.record_id.clear();
         self.owners.clear();
         self.locations.clear();
+        self.components.clear();
+        self.assembly.clear();
+        self.recalled = false;
+        self.bounds.clear();
+        self.reports.clear();
+        self.in_violation = false;
         self.unknown_fields.clear();
     }
 }
@@ -729,6 +980,484 @@ impl ::protobuf::reflect::ProtobufValue for Record_Location {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct Record_PropertyBound {
+    // message fields
+    pub name: ::std::string::String,
+    pub min: i64,
+    pub max: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Record_PropertyBound {
+    fn default() -> &'a Record_PropertyBound {
+        <Record_PropertyBound as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Record_PropertyBound {
+    pub fn new() -> Record_PropertyBound {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // sint64 min = 2;
+
+
+    pub fn get_min(&self) -> i64 {
+        self.min
+    }
+    pub fn clear_min(&mut self) {
+        self.min = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_min(&mut self, v: i64) {
+        self.min = v;
+    }
+
+    // sint64 max = 3;
+
+
+    pub fn get_max(&self) -> i64 {
+        self.max
+    }
+    pub fn clear_max(&mut self) {
+        self.max = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_max(&mut self, v: i64) {
+        self.max = v;
+    }
+}
+
+impl ::protobuf::Message for Record_PropertyBound {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.min = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.max = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        if self.min != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(2, self.min);
+        }
+        if self.max != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(3, self.max);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        if self.min != 0 {
+            os.write_sint64(2, self.min)?;
+        }
+        if self.max != 0 {
+            os.write_sint64(3, self.max)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Record_PropertyBound {
+        Record_PropertyBound::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "name",
+                    |m: &Record_PropertyBound| { &m.name },
+                    |m: &mut Record_PropertyBound| { &mut m.name },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "min",
+                    |m: &Record_PropertyBound| { &m.min },
+                    |m: &mut Record_PropertyBound| { &mut m.min },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "max",
+                    |m: &Record_PropertyBound| { &m.max },
+                    |m: &mut Record_PropertyBound| { &mut m.max },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Record_PropertyBound>(
+                    "Record_PropertyBound",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Record_PropertyBound {
+        static mut instance: ::protobuf::lazy::Lazy<Record_PropertyBound> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Record_PropertyBound,
+        };
+        unsafe {
+            instance.get(Record_PropertyBound::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Record_PropertyBound {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.min = 0;
+        self.max = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Record_PropertyBound {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Record_PropertyBound {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Record_PropertyReport {
+    // message fields
+    pub name: ::std::string::String,
+    pub value: i64,
+    pub timestamp: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Record_PropertyReport {
+    fn default() -> &'a Record_PropertyReport {
+        <Record_PropertyReport as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Record_PropertyReport {
+    pub fn new() -> Record_PropertyReport {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // sint64 value = 2;
+
+
+    pub fn get_value(&self) -> i64 {
+        self.value
+    }
+    pub fn clear_value(&mut self) {
+        self.value = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_value(&mut self, v: i64) {
+        self.value = v;
+    }
+
+    // uint64 timestamp = 3;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+}
+
+impl ::protobuf::Message for Record_PropertyReport {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_sint64()?;
+                    self.value = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.timestamp = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        if self.value != 0 {
+            my_size += ::protobuf::rt::value_varint_zigzag_size(2, self.value);
+        }
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        if self.value != 0 {
+            os.write_sint64(2, self.value)?;
+        }
+        if self.timestamp != 0 {
+            os.write_uint64(3, self.timestamp)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Record_PropertyReport {
+        Record_PropertyReport::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "name",
+                    |m: &Record_PropertyReport| { &m.name },
+                    |m: &mut Record_PropertyReport| { &mut m.name },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeSint64>(
+                    "value",
+                    |m: &Record_PropertyReport| { &m.value },
+                    |m: &mut Record_PropertyReport| { &mut m.value },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "timestamp",
+                    |m: &Record_PropertyReport| { &m.timestamp },
+                    |m: &mut Record_PropertyReport| { &mut m.timestamp },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Record_PropertyReport>(
+                    "Record_PropertyReport",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Record_PropertyReport {
+        static mut instance: ::protobuf::lazy::Lazy<Record_PropertyReport> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Record_PropertyReport,
+        };
+        unsafe {
+            instance.get(Record_PropertyReport::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Record_PropertyReport {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.value = 0;
+        self.timestamp = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Record_PropertyReport {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Record_PropertyReport {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
 #[derive(PartialEq,Clone,Default)]
 pub struct RecordContainer {
     // message fields
@@ -906,15 +1635,25 @@ impl ::protobuf::reflect::ProtobufValue for RecordContainer {
 }
 
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x0crecord.proto\"\xa2\x02\n\x06Record\x12\x1b\n\trecord_id\x18\x01\
+    \n\x0crecord.proto\"\xa1\x05\n\x06Record\x12\x1b\n\trecord_id\x18\x01\
     \x20\x01(\tR\x08recordId\x12%\n\x06owners\x18\x02\x20\x03(\x0b2\r.Record\
     .OwnerR\x06owners\x12.\n\tlocations\x18\x03\x20\x03(\x0b2\x10.Record.Loc\
-    ationR\tlocations\x1a@\n\x05Owner\x12\x19\n\x08agent_id\x18\x01\x20\x01(\
-    \tR\x07agentId\x12\x1c\n\ttimestamp\x18\x02\x20\x01(\x04R\ttimestamp\x1a\
-    b\n\x08Location\x12\x1a\n\x08latitude\x18\x01\x20\x01(\x12R\x08latitude\
-    \x12\x1c\n\tlongitude\x18\x02\x20\x01(\x12R\tlongitude\x12\x1c\n\ttimest\
-    amp\x18\x03\x20\x01(\x04R\ttimestamp\"4\n\x0fRecordContainer\x12!\n\x07e\
-    ntries\x18\x01\x20\x03(\x0b2\x07.RecordR\x07entriesb\x06proto3\
+    ationR\tlocations\x12\x1e\n\ncomponents\x18\x04\x20\x03(\tR\ncomponents\
+    \x12\x1a\n\x08assembly\x18\x05\x20\x01(\tR\x08assembly\x12\x1a\n\x08reca\
+    lled\x18\x06\x20\x01(\x08R\x08recalled\x12-\n\x06bounds\x18\x07\x20\x03(\
+    \x0b2\x15.Record.PropertyBoundR\x06bounds\x120\n\x07reports\x18\x08\x20\
+    \x03(\x0b2\x16.Record.PropertyReportR\x07reports\x12!\n\x0cin_violation\
+    \x18\t\x20\x01(\x08R\x0binViolation\x1a@\n\x05Owner\x12\x19\n\x08agent_i\
+    d\x18\x01\x20\x01(\tR\x07agentId\x12\x1c\n\ttimestamp\x18\x02\x20\x01(\
+    \x04R\ttimestamp\x1ab\n\x08Location\x12\x1a\n\x08latitude\x18\x01\x20\
+    \x01(\x12R\x08latitude\x12\x1c\n\tlongitude\x18\x02\x20\x01(\x12R\tlongi\
+    tude\x12\x1c\n\ttimestamp\x18\x03\x20\x01(\x04R\ttimestamp\x1aG\n\rPrope\
+    rtyBound\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04name\x12\x10\n\x03min\
+    \x18\x02\x20\x01(\x12R\x03min\x12\x10\n\x03max\x18\x03\x20\x01(\x12R\x03\
+    max\x1aX\n\x0ePropertyReport\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04na\
+    me\x12\x14\n\x05value\x18\x02\x20\x01(\x12R\x05value\x12\x1c\n\ttimestam\
+    p\x18\x03\x20\x01(\x04R\ttimestamp\"4\n\x0fRecordContainer\x12!\n\x07ent\
+    ries\x18\x01\x20\x03(\x0b2\x07.RecordR\x07entriesb\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
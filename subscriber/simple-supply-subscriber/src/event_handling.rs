@@ -0,0 +1,420 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use chrono::Utc;
+use diesel::pg::PgConnection;
+use diesel::Connection;
+use protos::agent::AgentContainer;
+use protos::record::{Record, RecordContainer};
+use rest_api::database::{self, MAX_BLOCK_NUMBER};
+use rest_api::model::{NewAgent, NewBlock, NewRecord, NewRecordLocation, NewRecordOwner};
+use sawtooth_sdk::messages::events::{Event, EventList};
+use sawtooth_sdk::messages::transaction_receipt::{StateChangeList, StateChange_Type};
+use simple_supply_tp::addresser::{get_address_type, AddressSpace};
+use simple_supply_tp::handler::{RECORD_RECALLED_EVENT, THRESHOLD_VIOLATION_EVENT};
+
+const BLOCK_COMMIT_EVENT: &str = "sawtooth/block-commit";
+const STATE_DELTA_EVENT: &str = "sawtooth/state-delta";
+/// How far ahead of the subscriber's clock a transaction may be dated and
+/// still date its block, in seconds
+const MAX_CLOCK_SKEW: i64 = 5 * 60;
+
+#[derive(Debug)]
+pub enum EventError {
+    ParseError(String),
+    DatabaseError(diesel::result::Error),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventError::ParseError(msg) => write!(f, "failed to parse events: {}", msg),
+            EventError::DatabaseError(err) => write!(f, "database error: {}", err),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for EventError {
+    fn from(err: diesel::result::Error) -> Self {
+        EventError::DatabaseError(err)
+    }
+}
+
+impl From<protobuf::ProtobufError> for EventError {
+    fn from(err: protobuf::ProtobufError) -> Self {
+        EventError::ParseError(err.to_string())
+    }
+}
+
+/// Applies the events of a single block to the reporting database
+pub fn handle_events(conn: &PgConnection, content: &[u8]) -> Result<(), EventError> {
+    let event_list: EventList = protobuf::parse_from_bytes(content)?;
+    let events = event_list.get_events();
+    let (block_num, block_id) = parse_new_block(events)?;
+    let content_timestamp = content_timestamp(events, Utc::now().timestamp())?;
+
+    conn.transaction(|| {
+        if resolve_if_forked(conn, block_num, &block_id)? {
+            debug!("Skipping duplicate block {} ({})", block_num, block_id);
+            return Ok(());
+        }
+
+        // Kept from going backwards, so that the last block at or before a
+        // time is the state at that time
+        let timestamp = database::fetch_block_timestamp_before(conn, block_num)?
+            .into_iter()
+            .chain(content_timestamp)
+            .max();
+        database::insert_block(
+            conn,
+            &NewBlock {
+                block_num,
+                block_id,
+                timestamp,
+            },
+        )?;
+        apply_state_changes(conn, events, block_num)?;
+        apply_recalls(conn, events, block_num)?;
+        log_violations(events)?;
+        Ok(())
+    })
+}
+
+fn parse_new_block(events: &[Event]) -> Result<(i64, String), EventError> {
+    let block_commit = events
+        .iter()
+        .find(|event| event.get_event_type() == BLOCK_COMMIT_EVENT)
+        .ok_or_else(|| EventError::ParseError("no block commit event".to_string()))?;
+
+    let block_num = get_attribute(block_commit, "block_num")?
+        .parse::<i64>()
+        .map_err(|err| EventError::ParseError(err.to_string()))?;
+    let block_id = get_attribute(block_commit, "block_id")?.to_string();
+    Ok((block_num, block_id))
+}
+
+/// Dates a block by the latest transaction timestamp among the changes and
+/// events it carries. Block commit events have no time of their own, and the
+/// time they reach the subscriber depends on how far behind it is.
+///
+/// Signers pick their own timestamps and block times never go back, so a
+/// single one from the far future would date every later block after it.
+/// Timestamps more than `MAX_CLOCK_SKEW` past `now` are left out for that
+/// reason, as are those too large for the database.
+fn content_timestamp(events: &[Event], now: i64) -> Result<Option<i64>, EventError> {
+    let mut timestamps = Vec::new();
+    for event in events {
+        match event.get_event_type() {
+            STATE_DELTA_EVENT => {
+                let changes: StateChangeList = protobuf::parse_from_bytes(event.get_data())?;
+                for change in changes.get_state_changes() {
+                    if change.get_field_type() != StateChange_Type::SET {
+                        continue;
+                    }
+                    match get_address_type(change.get_address()) {
+                        AddressSpace::Agent => {
+                            let container: AgentContainer =
+                                protobuf::parse_from_bytes(change.get_value())?;
+                            timestamps.extend(
+                                container
+                                    .get_entries()
+                                    .iter()
+                                    .map(|agent| agent.get_timestamp()),
+                            );
+                        }
+                        AddressSpace::Record => {
+                            let container: RecordContainer =
+                                protobuf::parse_from_bytes(change.get_value())?;
+                            timestamps
+                                .extend(container.get_entries().iter().filter_map(last_updated));
+                        }
+                        AddressSpace::OtherFamily => (),
+                    }
+                }
+            }
+            RECORD_RECALLED_EVENT | THRESHOLD_VIOLATION_EVENT => {
+                timestamps.push(parse_timestamp(event)?);
+            }
+            _ => (),
+        }
+    }
+    Ok(timestamps
+        .into_iter()
+        .filter_map(|timestamp| i64::try_from(timestamp).ok())
+        .filter(|&timestamp| timestamp <= now.saturating_add(MAX_CLOCK_SKEW))
+        .max())
+}
+
+/// Returns true if the block has already been handled. When a different
+/// block was stored at the same height, the old fork is dropped first.
+fn resolve_if_forked(
+    conn: &PgConnection,
+    block_num: i64,
+    block_id: &str,
+) -> Result<bool, EventError> {
+    match database::fetch_block_id(conn, block_num)? {
+        Some(ref existing) if existing == block_id => Ok(true),
+        Some(existing) => {
+            info!(
+                "Fork detected: replacing {} ({}) with {} ({})",
+                existing, block_num, block_id, block_num
+            );
+            database::drop_fork(conn, block_num)?;
+            Ok(false)
+        }
+        None => Ok(false),
+    }
+}
+
+fn apply_state_changes(
+    conn: &PgConnection,
+    events: &[Event],
+    block_num: i64,
+) -> Result<(), EventError> {
+    for event in events
+        .iter()
+        .filter(|event| event.get_event_type() == STATE_DELTA_EVENT)
+    {
+        let changes: StateChangeList = protobuf::parse_from_bytes(event.get_data())?;
+        for change in changes.get_state_changes() {
+            if change.get_field_type() != StateChange_Type::SET {
+                continue;
+            }
+            match get_address_type(change.get_address()) {
+                AddressSpace::Agent => apply_agents(conn, change.get_value(), block_num)?,
+                AddressSpace::Record => apply_records(conn, change.get_value(), block_num)?,
+                AddressSpace::OtherFamily => (),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_agents(conn: &PgConnection, data: &[u8], block_num: i64) -> Result<(), EventError> {
+    let container: AgentContainer = protobuf::parse_from_bytes(data)?;
+    for agent in container.get_entries() {
+        database::insert_agent(
+            conn,
+            &NewAgent {
+                public_key: agent.get_public_key().to_string(),
+                name: agent.get_name().to_string(),
+                timestamp: agent.get_timestamp() as i64,
+                start_block_num: block_num,
+                end_block_num: MAX_BLOCK_NUMBER,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn apply_records(conn: &PgConnection, data: &[u8], block_num: i64) -> Result<(), EventError> {
+    let container: RecordContainer = protobuf::parse_from_bytes(data)?;
+    for record in container.get_entries() {
+        let record_id = record.get_record_id();
+        let owners: Vec<NewRecordOwner> = record
+            .get_owners()
+            .iter()
+            .map(|owner| NewRecordOwner {
+                record_id: record_id.to_string(),
+                agent_id: owner.get_agent_id().to_string(),
+                timestamp: owner.get_timestamp() as i64,
+                start_block_num: block_num,
+                end_block_num: MAX_BLOCK_NUMBER,
+            })
+            .collect();
+        let locations: Vec<NewRecordLocation> = record
+            .get_locations()
+            .iter()
+            .map(|location| NewRecordLocation {
+                record_id: record_id.to_string(),
+                latitude: location.get_latitude(),
+                longitude: location.get_longitude(),
+                timestamp: location.get_timestamp() as i64,
+                start_block_num: block_num,
+                end_block_num: MAX_BLOCK_NUMBER,
+            })
+            .collect();
+        let assembly = match record.get_assembly() {
+            "" => None,
+            assembly => Some(assembly.to_string()),
+        };
+        let latest_owner = record.get_owners().last();
+        let latest_location = record.get_locations().last();
+
+        database::insert_record(
+            conn,
+            &NewRecord {
+                record_id: record_id.to_string(),
+                assembly,
+                in_violation: record.get_in_violation(),
+                owner: latest_owner.map(|owner| owner.get_agent_id().to_string()),
+                latitude: latest_location.map(|location| location.get_latitude()),
+                longitude: latest_location.map(|location| location.get_longitude()),
+                last_updated: last_updated(record).map(|timestamp| timestamp as i64),
+                start_block_num: block_num,
+                end_block_num: MAX_BLOCK_NUMBER,
+            },
+            &owners,
+            &locations,
+        )?;
+    }
+    Ok(())
+}
+
+/// The timestamp of the latest owner, location or report of a record
+fn last_updated(record: &Record) -> Option<u64> {
+    record
+        .get_owners()
+        .last()
+        .map(|owner| owner.get_timestamp())
+        .into_iter()
+        .chain(
+            record
+                .get_locations()
+                .last()
+                .map(|location| location.get_timestamp()),
+        )
+        .chain(
+            record
+                .get_reports()
+                .last()
+                .map(|report| report.get_timestamp()),
+        )
+        .max()
+}
+
+fn apply_recalls(conn: &PgConnection, events: &[Event], block_num: i64) -> Result<(), EventError> {
+    for event in events
+        .iter()
+        .filter(|event| event.get_event_type() == RECORD_RECALLED_EVENT)
+    {
+        let record_id = get_attribute(event, "record_id")?;
+        let timestamp = parse_timestamp(event)? as i64;
+        let flagged = database::insert_recall(conn, record_id, timestamp, block_num)?;
+        info!("Record {} recalled, {} records flagged", record_id, flagged);
+    }
+    Ok(())
+}
+
+/// Logs each reading which moved a property out of its bound. The record's
+/// `in_violation` flag already arrives with its state change, so the event
+/// only adds which reading caused it.
+fn log_violations(events: &[Event]) -> Result<(), EventError> {
+    for event in events
+        .iter()
+        .filter(|event| event.get_event_type() == THRESHOLD_VIOLATION_EVENT)
+    {
+        warn!(
+            "Record {} out of bounds: {} is {}, expected {} to {}",
+            get_attribute(event, "record_id")?,
+            get_attribute(event, "name")?,
+            get_attribute(event, "value")?,
+            get_attribute(event, "min")?,
+            get_attribute(event, "max")?,
+        );
+    }
+    Ok(())
+}
+
+/// The timestamp attribute of an event, which holds the payload timestamp
+fn parse_timestamp(event: &Event) -> Result<u64, EventError> {
+    get_attribute(event, "timestamp")?
+        .parse::<u64>()
+        .map_err(|err| EventError::ParseError(err.to_string()))
+}
+
+fn get_attribute<'a>(event: &'a Event, key: &str) -> Result<&'a str, EventError> {
+    event
+        .get_attributes()
+        .iter()
+        .find(|attribute| attribute.get_key() == key)
+        .map(|attribute| attribute.get_value())
+        .ok_or_else(|| {
+            EventError::ParseError(format!(
+                "{} event has no {} attribute",
+                event.get_event_type(),
+                key
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::Message;
+    use protos::agent::Agent;
+    use sawtooth_sdk::messages::events::Event_Attribute;
+    use sawtooth_sdk::messages::transaction_receipt::StateChange;
+    use simple_supply_tp::addresser;
+
+    const NOW: i64 = 1_600_000_000;
+
+    fn agents_changed(timestamps: &[u64]) -> Event {
+        let agents = timestamps
+            .iter()
+            .map(|&timestamp| Agent {
+                public_key: "agent".to_string(),
+                timestamp,
+                ..Default::default()
+            })
+            .collect();
+        let container = AgentContainer {
+            entries: protobuf::RepeatedField::from_vec(agents),
+            ..Default::default()
+        };
+        let changes = StateChangeList {
+            state_changes: protobuf::RepeatedField::from_vec(vec![StateChange {
+                address: addresser::get_agent_address("agent"),
+                value: container.write_to_bytes().unwrap(),
+                field_type: StateChange_Type::SET,
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        Event {
+            event_type: STATE_DELTA_EVENT.to_string(),
+            data: changes.write_to_bytes().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn recalled(timestamp: &str) -> Event {
+        Event {
+            event_type: RECORD_RECALLED_EVENT.to_string(),
+            attributes: protobuf::RepeatedField::from_vec(vec![Event_Attribute {
+                key: "timestamp".to_string(),
+                value: timestamp.to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn blocks_are_dated_by_their_latest_transaction() {
+        let events = [
+            agents_changed(&[NOW as u64 - 20, NOW as u64 - 10]),
+            recalled(&(NOW - 15).to_string()),
+        ];
+        assert_eq!(content_timestamp(&events, NOW).unwrap(), Some(NOW - 10));
+        assert_eq!(content_timestamp(&[], NOW).unwrap(), None);
+    }
+
+    #[test]
+    fn timestamps_from_the_far_future_are_ignored() {
+        let slightly_ahead = NOW + MAX_CLOCK_SKEW;
+        let events = [
+            agents_changed(&[NOW as u64, u64::MAX, i64::MAX as u64]),
+            recalled(&(slightly_ahead + 1).to_string()),
+            recalled(&slightly_ahead.to_string()),
+        ];
+        assert_eq!(
+            content_timestamp(&events, NOW).unwrap(),
+            Some(slightly_ahead)
+        );
+
+        let hostile = [agents_changed(&[u64::MAX]), recalled(&u64::MAX.to_string())];
+        assert_eq!(content_timestamp(&hostile, NOW).unwrap(), None);
+    }
+}
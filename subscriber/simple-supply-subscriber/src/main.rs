@@ -3,6 +3,7 @@ extern crate log;
 #[macro_use]
 extern crate diesel;
 
+use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -10,6 +11,7 @@ use std::thread;
 use ctrlc;
 use dotenv::dotenv;
 
+mod event_handling;
 mod subscriber;
 
 fn main() {
@@ -17,14 +19,15 @@ fn main() {
     env_logger::init();
 
     let (subscriber, context) = subscriber::Subscriber::new();
-    let subscriber_thread = thread::spawn(move || {
-        subscriber.start();
-    });
+    let subscriber_thread = thread::spawn(move || subscriber.start());
 
     ctrlc::set_handler(move || {
         context.cancel();
     })
     .expect("Error setting Ctrl-C handler");
 
-    subscriber_thread.join().expect("subscriber panicked");
+    if let Err(err) = subscriber_thread.join().expect("subscriber panicked") {
+        error!("Subscriber stopped: {}", err);
+        process::exit(1);
+    }
 }
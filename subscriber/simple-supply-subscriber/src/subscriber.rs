@@ -1,10 +1,12 @@
 use std::env;
 use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
 use std::time::Duration;
 
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
 use protobuf::Message;
+use rest_api::database;
 use rest_api::messaging;
 use sawtooth_sdk::messages::client_event;
 use sawtooth_sdk::messages::events;
@@ -12,8 +14,19 @@ use sawtooth_sdk::messages::validator;
 use sawtooth_sdk::messaging::stream::{MessageConnection, MessageReceiver, MessageSender};
 use sawtooth_sdk::messaging::zmq_stream;
 use simple_supply_tp::addresser;
+use simple_supply_tp::handler::{RECORD_RECALLED_EVENT, THRESHOLD_VIOLATION_EVENT};
 use uuid::Uuid;
 
+use crate::event_handling;
+
+const NULL_BLOCK_ID: &str = "0000000000000000";
+/// How many of the latest stored blocks are offered to the validator, so
+/// that it can find where the reporting database left off even after a fork
+const KNOWN_BLOCK_COUNT: i64 = 15;
+/// How many times a block is applied before the subscriber gives up on it
+const BLOCK_ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
 #[derive(Clone)]
 pub struct Context {
     sender: Sender<()>,
@@ -52,9 +65,15 @@ impl Subscriber {
         (subscriber, context)
     }
 
-    pub fn start(&self) {
+    /// Subscribes from the last block in the reporting database and applies
+    /// blocks until cancelled. Returns an error when a block cannot be
+    /// applied, so that a restart picks up from that block again rather than
+    /// leaving a gap.
+    pub fn start(&self) -> Result<(), String> {
         debug!("Subscribing to state delta events");
 
+        let last_known_block_ids = self.last_known_block_ids()?;
+
         let validator_url = env::var("VALIDATOR_URL").expect("VALIDATOR_URL is not set");
         let connection = zmq_stream::ZmqMessageConnection::new(&validator_url);
         let (mut validator_sender, validator_receiver) = connection.create();
@@ -75,10 +94,24 @@ impl Subscriber {
             ..Default::default()
         };
 
-        let null_block_id = "0000000000000000".to_string();
+        let recall_sub = events::EventSubscription {
+            event_type: RECORD_RECALLED_EVENT.to_string(),
+            ..Default::default()
+        };
+
+        let violation_sub = events::EventSubscription {
+            event_type: THRESHOLD_VIOLATION_EVENT.to_string(),
+            ..Default::default()
+        };
+
         let request = client_event::ClientEventsSubscribeRequest {
-            subscriptions: protobuf::RepeatedField::from_vec(vec![block_sub, delta_sub]),
-            last_known_block_ids: ::protobuf::RepeatedField::from_vec(vec![null_block_id]),
+            subscriptions: protobuf::RepeatedField::from_vec(vec![
+                block_sub,
+                delta_sub,
+                recall_sub,
+                violation_sub,
+            ]),
+            last_known_block_ids: ::protobuf::RepeatedField::from_vec(last_known_block_ids),
             ..Default::default()
         };
 
@@ -89,10 +122,7 @@ impl Subscriber {
             &request_bytes,
         ) {
             Ok(fut) => fut,
-            Err(e) => {
-                error!("{}", e);
-                return; // TODO error handling
-            }
+            Err(e) => return Err(e.to_string()),
         };
         let response: client_event::ClientEventsSubscribeResponse;
         loop {
@@ -101,21 +131,17 @@ impl Subscriber {
                     response = protobuf::parse_from_bytes(&message.content[..]).unwrap();
                     break;
                 }
-                Err(err) => {
-                    error!("{}", err);
-                    return; // TODO error handling
-                }
+                Err(err) => return Err(err.to_string()),
             };
         }
 
         match response.status {
             client_event::ClientEventsSubscribeResponse_Status::OK => {}
             client_event::ClientEventsSubscribeResponse_Status::INVALID_FILTER => {
-                return error!("invalid filter");
+                return Err("invalid filter".to_string());
             }
             client_event::ClientEventsSubscribeResponse_Status::UNKNOWN_BLOCK => {
-                // Pytnonのコードは再スタートしている self.start()
-                return error!("unknown block");
+                return Err("none of the stored blocks are known to the validator".to_string());
             }
             _ => unreachable!("status unset"),
         }
@@ -123,19 +149,19 @@ impl Subscriber {
         debug!("Successfully subscribed to state delta events");
 
         // https://github.com/hyperledger/sawtooth-sdk-rust/blob/5e46e844d5c0615dfd05b3b885980c42e09c9a69/src/consensus/zmq_driver.rs
-        loop {
+        let result = loop {
             match validator_receiver.recv_timeout(Duration::from_millis(1000)) {
                 Err(RecvTimeoutError::Timeout) => {
                     if self.cancel_receiver.try_recv().is_ok() {
-                        break;
+                        break Ok(());
                     }
                 }
                 Err(RecvTimeoutError::Disconnected) => {
                     // break Err(Error::ReceiveError("Sender disconnected".into()));
-                    break error!("error: sender disconnected");
+                    break Err("sender disconnected".to_string());
                 }
                 Ok(Err(err)) => {
-                    break error!("unexpected error while receiving: {}", err);
+                    break Err(format!("unexpected error while receiving: {}", err));
                     // break Err(Error::ReceiveError(format!(
                     //     "Unexpected error while receiving: {}",
                     //     err
@@ -143,17 +169,57 @@ impl Subscriber {
                 }
                 Ok(Ok(msg)) => {
                     debug!("{:?}", msg);
-                    // if let Err(err) =
-                    //     handle_update(&msg, &mut validator_sender, &mut update_sender)
-                    // {
-                    //     break Err(err);
-                    // }
+                    if msg.get_message_type() == validator::Message_MessageType::CLIENT_EVENTS {
+                        if let Err(err) = self.handle_events(msg.get_content()) {
+                            break Err(err);
+                        }
+                    }
                     if self.cancel_receiver.try_recv().is_ok() {
-                        break;
+                        break Ok(());
                     }
                 }
             }
-        }
+        };
         validator_sender.close();
+        result
+    }
+
+    /// The latest stored block ids, or the null block id when nothing has
+    /// been stored yet so that the validator replays the whole chain
+    fn last_known_block_ids(&self) -> Result<Vec<String>, String> {
+        let conn = self.pool.get().map_err(|err| err.to_string())?;
+        let block_ids = database::fetch_latest_block_ids(&conn, KNOWN_BLOCK_COUNT)
+            .map_err(|err| err.to_string())?;
+        if block_ids.is_empty() {
+            Ok(vec![NULL_BLOCK_ID.to_string()])
+        } else {
+            Ok(block_ids)
+        }
+    }
+
+    /// Applies a block, retrying while the database is unavailable. Each
+    /// attempt runs in its own transaction, so a failed one leaves nothing
+    /// behind.
+    fn handle_events(&self, content: &[u8]) -> Result<(), String> {
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .pool
+                .get()
+                .map_err(|err| format!("unable to get a database connection: {}", err))
+                .and_then(|conn| {
+                    event_handling::handle_events(&conn, content)
+                        .map_err(|err| format!("unable to handle events: {}", err))
+                });
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < BLOCK_ATTEMPTS => {
+                    warn!("{} (attempt {} of {})", err, attempt, BLOCK_ATTEMPTS);
+                    thread::sleep(RETRY_DELAY);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
     }
 }